@@ -0,0 +1,119 @@
+use ratatui::layout::Constraint;
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+
+// Written as 12 (fixed), "30%" (a share of the table) or
+// { min = 10, max = 40 } with either bound left out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ColumnWidth {
+    Length(u16),
+    Percentage(u16),
+    Range { min: u16, max: Option<u16> },
+}
+
+impl ColumnWidth {
+    // A range with both bounds is laid out by its maximum, its minimum is
+    // then enforced by whoever lays out the columns.
+    pub const fn constraint(self) -> Constraint {
+        match self {
+            Self::Length(length) => Constraint::Length(length),
+            Self::Percentage(percentage) => Constraint::Percentage(percentage),
+            Self::Range { min, max: None } => Constraint::Min(min),
+            Self::Range { max: Some(max), .. } => Constraint::Max(max),
+        }
+    }
+
+    pub const fn min(self) -> u16 {
+        match self {
+            Self::Range { min, .. } => min,
+            _ => 0,
+        }
+    }
+
+    pub const fn percentage(self) -> Option<u16> {
+        match self {
+            Self::Percentage(percentage) => Some(percentage),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ColumnWidth {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Bounds {
+            min: Option<u16>,
+            max: Option<u16>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum WidthValue {
+            Length(u16),
+            Percentage(String),
+            Bounds(Bounds),
+        }
+
+        match WidthValue::deserialize(deserializer)? {
+            WidthValue::Length(length) => Ok(Self::Length(length)),
+            WidthValue::Percentage(text) => {
+                match text.strip_suffix('%').map(|number| number.trim().parse()) {
+                    Some(Ok(percentage @ 0..=100)) => Ok(Self::Percentage(percentage)),
+                    _ => Err(D::Error::custom(format!(
+                        "invalid width \"{text}\", expected a percentage like \"30%\""
+                    ))),
+                }
+            }
+            WidthValue::Bounds(Bounds {
+                min: None,
+                max: None,
+            }) => Err(D::Error::custom("a width range needs a min, a max or both")),
+            WidthValue::Bounds(Bounds {
+                min: Some(min),
+                max: Some(max),
+            }) if min > max => Err(D::Error::custom(format!(
+                "the width range's min ({min}) is above its max ({max})"
+            ))),
+            WidthValue::Bounds(Bounds { min, max }) => Ok(Self::Range {
+                min: min.unwrap_or(0),
+                max,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(width: &str) -> Result<ColumnWidth, toml::de::Error> {
+        #[derive(Deserialize)]
+        struct Column {
+            width: ColumnWidth,
+        }
+
+        toml::from_str::<Column>(&format!("width = {width}")).map(|column| column.width)
+    }
+
+    #[test]
+    fn parses_column_widths() {
+        assert_eq!(parse("12").unwrap(), ColumnWidth::Length(12));
+        assert_eq!(parse("\"30%\"").unwrap(), ColumnWidth::Percentage(30));
+        assert_eq!(
+            parse("{ min = 10 }").unwrap(),
+            ColumnWidth::Range { min: 10, max: None }
+        );
+        assert_eq!(
+            parse("{ min = 10, max = 40 }").unwrap(),
+            ColumnWidth::Range {
+                min: 10,
+                max: Some(40)
+            }
+        );
+
+        assert!(parse("\"130%\"").is_err());
+        assert!(parse("\"wide\"").is_err());
+        assert!(parse("{}").is_err());
+        assert!(parse("{ min = 40, max = 10 }").is_err());
+    }
+}
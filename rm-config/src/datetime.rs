@@ -0,0 +1,115 @@
+use chrono::{
+    format::{Item, StrftimeItems},
+    Local, TimeZone,
+};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+
+const DEFAULT_PATTERN: &str = "%Y-%m-%d %H:%M";
+
+// Written as a strftime pattern, or "relative" for e.g. "2 days ago"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum DateTimeFormat {
+    Relative,
+    Pattern(String),
+}
+
+impl Default for DateTimeFormat {
+    fn default() -> Self {
+        Self::Pattern(DEFAULT_PATTERN.to_string())
+    }
+}
+
+impl DateTimeFormat {
+    // Takes Unix seconds, shown in the local timezone. Transmission gives 0
+    // for what hasn't happened yet.
+    pub fn format(&self, timestamp: i64) -> String {
+        self.format_at(timestamp, Local::now().timestamp())
+    }
+
+    fn format_at(&self, timestamp: i64, now: i64) -> String {
+        if timestamp <= 0 {
+            return "never".to_string();
+        }
+
+        match self {
+            Self::Relative => relative(now - timestamp),
+            Self::Pattern(pattern) => Local
+                .timestamp_opt(timestamp, 0)
+                .earliest()
+                .map(|date| date.format(pattern).to_string())
+                .unwrap_or_else(|| "never".to_string()),
+        }
+    }
+}
+
+fn relative(seconds_ago: i64) -> String {
+    const UNITS: [(i64, &str); 5] = [
+        (365 * 24 * 60 * 60, "year"),
+        (30 * 24 * 60 * 60, "month"),
+        (24 * 60 * 60, "day"),
+        (60 * 60, "hour"),
+        (60, "minute"),
+    ];
+
+    // A clock a bit ahead of the daemon's shouldn't read "in 5 seconds"
+    let Some((count, unit)) = UNITS
+        .iter()
+        .map(|(seconds, unit)| (seconds_ago.abs() / seconds, unit))
+        .find(|(count, _)| *count > 0)
+    else {
+        return "just now".to_string();
+    };
+
+    let plural = if count == 1 { "" } else { "s" };
+    if seconds_ago < 0 {
+        format!("in {count} {unit}{plural}")
+    } else {
+        format!("{count} {unit}{plural} ago")
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTimeFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        if text == "relative" {
+            return Ok(Self::Relative);
+        }
+
+        // chrono panics when formatting with a broken pattern
+        if StrftimeItems::new(&text).any(|item| item == Item::Error) {
+            return Err(D::Error::custom(format!(
+                "invalid datetime_format \"{text}\", expected \"relative\" or a strftime \
+                 pattern like \"{DEFAULT_PATTERN}\""
+            )));
+        }
+        Ok(Self::Pattern(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_relative_dates() {
+        let now = 1_700_000_000;
+        let format = DateTimeFormat::Relative;
+        assert_eq!(format.format_at(now - 30, now), "just now");
+        assert_eq!(format.format_at(now - 60, now), "1 minute ago");
+        assert_eq!(format.format_at(now - 2 * 24 * 60 * 60, now), "2 days ago");
+        assert_eq!(format.format_at(now + 3 * 60 * 60, now), "in 3 hours");
+        assert_eq!(format.format_at(0, now), "never");
+        assert_eq!(DateTimeFormat::default().format_at(0, now), "never");
+    }
+
+    #[test]
+    fn rejects_broken_patterns() {
+        let parse = |text: &str| DateTimeFormat::deserialize(toml::Value::from(text));
+        assert_eq!(parse("relative").unwrap(), DateTimeFormat::Relative);
+        assert_eq!(
+            parse("%d.%m.%Y").unwrap(),
+            DateTimeFormat::Pattern("%d.%m.%Y".to_string())
+        );
+        assert!(parse("%Y-%Q").is_err());
+    }
+}
@@ -0,0 +1,764 @@
+use std::{collections::HashMap, fs::File, io::Write, path::PathBuf};
+
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::{KeyCode, KeyModifiers};
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+use crate::Config;
+
+pub const DEFAULT_KEYMAP: &str = include_str!("../defaults/keymap.toml");
+
+#[derive(Debug, Deserialize)]
+pub struct KeymapConfig {
+    pub general: KeybindsHolder<GeneralAction>,
+    pub torrents_tab: KeybindsHolder<TorrentsAction>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KeybindsHolder<T> {
+    pub keybindings: Vec<Keybinding<T>>,
+}
+
+// Also what general.tabs lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tab {
+    Torrents,
+    Search,
+}
+
+// A key, or a sequence of keys (a chord) that have to be pressed one after another.
+pub type KeySequence = Vec<(KeyCode, KeyModifiers)>;
+
+#[derive(Debug)]
+pub struct Keybinding<T> {
+    pub on: Vec<KeyCode>,
+    // Applied to every key of `on`
+    pub modifiers: Vec<KeyModifier>,
+    pub action: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum GeneralAction {
+    ShowHelp,
+    Quit,
+    SoftQuit,
+    SwitchToTorrents,
+    SwitchToSearch,
+    Left,
+    Right,
+    Down,
+    Up,
+    Search,
+    ChangeFocus,
+    Confirm,
+    Select,
+    ScrollPageDown,
+    ScrollPageUp,
+    GoToBeginning,
+    GoToEnd,
+    ReloadConfig,
+    Refresh,
+    SwitchServer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum TorrentsAction {
+    AddMagnet,
+    SelectUp,
+    SelectDown,
+    Pause,
+    StartNow,
+    StartAll,
+    StopAll,
+    DeleteWithoutFiles,
+    DeleteWithFiles,
+    UndoDelete,
+    Move,
+    SetLocation,
+    Verify,
+    Reannounce,
+    ShowFiles,
+    ShowInfo,
+    ShowStats,
+    SpeedLimit,
+    SortBy,
+    ToggleSortOrder,
+    CycleStatusFilter,
+    ToggleFinished,
+    ToggleAltSpeed,
+    CopyMagnet,
+    OpenDownloadDir,
+    Export,
+    CyclePriority,
+    GlobalSpeedLimit,
+    SeedLimits,
+    QueueUp,
+    QueueDown,
+    QueueTop,
+    QueueBottom,
+    EditLabels,
+    EditTrackers,
+    Rename,
+    ToggleDetails,
+    ToggleRowHeight,
+    GoToRow,
+}
+
+impl GeneralAction {
+    // Every action, so that none is left out of the default keymap
+    pub const ALL: [Self; 20] = [
+        Self::ShowHelp,
+        Self::Quit,
+        Self::SoftQuit,
+        Self::SwitchToTorrents,
+        Self::SwitchToSearch,
+        Self::Left,
+        Self::Right,
+        Self::Down,
+        Self::Up,
+        Self::Search,
+        Self::ChangeFocus,
+        Self::Confirm,
+        Self::Select,
+        Self::ScrollPageDown,
+        Self::ScrollPageUp,
+        Self::GoToBeginning,
+        Self::GoToEnd,
+        Self::ReloadConfig,
+        Self::Refresh,
+        Self::SwitchServer,
+    ];
+
+    pub const fn desc(self) -> &'static str {
+        match self {
+            Self::ShowHelp => "show/hide help",
+            Self::Quit => "quit Rustmission / a popup",
+            Self::SoftQuit => "close a popup / task",
+            Self::SwitchToTorrents => "switch to torrents tab",
+            Self::SwitchToSearch => "switch to search tab",
+            Self::Left => "switch to tab left of current tab",
+            Self::Right => "switch to tab right of current tab",
+            Self::Down => "move down",
+            Self::Up => "move up",
+            Self::Search => "search or filter",
+            Self::ChangeFocus => "switch focus",
+            Self::Confirm => "confirm",
+            Self::Select => "select",
+            Self::ScrollPageDown => "scroll page down",
+            Self::ScrollPageUp => "scroll page up",
+            Self::GoToBeginning => "scroll to the beginning",
+            Self::GoToEnd => "scroll to the end",
+            Self::ReloadConfig => "reload the keymap",
+            Self::Refresh => "refresh torrents and stats",
+            Self::SwitchServer => "switch to the next server",
+        }
+    }
+}
+
+impl TorrentsAction {
+    // Every action, so that none is left out of the default keymap
+    pub const ALL: [Self; 39] = [
+        Self::AddMagnet,
+        Self::SelectUp,
+        Self::SelectDown,
+        Self::Pause,
+        Self::StartNow,
+        Self::StartAll,
+        Self::StopAll,
+        Self::DeleteWithoutFiles,
+        Self::DeleteWithFiles,
+        Self::UndoDelete,
+        Self::Move,
+        Self::SetLocation,
+        Self::Verify,
+        Self::Reannounce,
+        Self::ShowFiles,
+        Self::ShowInfo,
+        Self::ShowStats,
+        Self::SpeedLimit,
+        Self::SortBy,
+        Self::ToggleSortOrder,
+        Self::CycleStatusFilter,
+        Self::ToggleFinished,
+        Self::ToggleAltSpeed,
+        Self::CopyMagnet,
+        Self::OpenDownloadDir,
+        Self::Export,
+        Self::CyclePriority,
+        Self::GlobalSpeedLimit,
+        Self::SeedLimits,
+        Self::QueueUp,
+        Self::QueueDown,
+        Self::QueueTop,
+        Self::QueueBottom,
+        Self::EditLabels,
+        Self::EditTrackers,
+        Self::Rename,
+        Self::ToggleDetails,
+        Self::ToggleRowHeight,
+        Self::GoToRow,
+    ];
+
+    pub const fn desc(self) -> &'static str {
+        match self {
+            Self::AddMagnet => "add a magnet url",
+            Self::SelectUp => "extend the selection up",
+            Self::SelectDown => "extend the selection down",
+            Self::Pause => "pause/unpause a torrent",
+            Self::StartNow => "start a torrent now, ignoring the queue",
+            Self::StartAll => "start all torrents",
+            Self::StopAll => "stop all torrents",
+            Self::DeleteWithoutFiles => "delete a torrent without files",
+            Self::DeleteWithFiles => "delete a torrent with files",
+            Self::UndoDelete => "add the last deleted torrents again",
+            Self::Move => "move data of a torrent",
+            Self::SetLocation => "set where a torrent's data is, without moving it",
+            Self::Verify => "verify local data of a torrent",
+            Self::Reannounce => "reannounce a torrent to its trackers",
+            Self::ShowFiles => "show files of a torrent",
+            Self::ShowInfo => "show details of a torrent",
+            Self::ShowStats => "show statistics",
+            Self::SpeedLimit => "set speed limits of a torrent",
+            Self::SortBy => "cycle the column torrents are sorted by",
+            Self::ToggleSortOrder => "reverse the sort order",
+            Self::CycleStatusFilter => "cycle the status filter",
+            Self::ToggleFinished => "hide/show finished torrents",
+            Self::ToggleAltSpeed => "toggle alternative speed limits",
+            Self::CopyMagnet => "copy the magnet link",
+            Self::OpenDownloadDir => "open in the file manager",
+            Self::Export => "export the shown torrents to a file",
+            Self::CyclePriority => "change priority of a file",
+            Self::GlobalSpeedLimit => "set global speed limits",
+            Self::SeedLimits => "set seed ratio and idle limits of a torrent",
+            Self::QueueUp => "move up in the queue",
+            Self::QueueDown => "move down in the queue",
+            Self::QueueTop => "move to the top of the queue",
+            Self::QueueBottom => "move to the bottom of the queue",
+            Self::EditLabels => "edit labels",
+            Self::EditTrackers => "add or remove trackers",
+            Self::Rename => "rename a torrent or file",
+            Self::ToggleDetails => "toggle the details pane",
+            Self::ToggleRowHeight => "toggle compact/expanded rows",
+            Self::GoToRow => "go to a row by its number",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyModifier {
+    None,
+    Ctrl,
+    Shift,
+    // Terminals sending ESC before the key are reported by crossterm as ALT
+    // too, while macOS terminals need "Option as Meta" for this to work.
+    Alt,
+}
+
+impl KeyModifier {
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Ctrl => "CTRL",
+            Self::Shift => "SHIFT",
+            Self::Alt => "ALT",
+        }
+    }
+}
+
+impl From<KeyModifier> for KeyModifiers {
+    fn from(value: KeyModifier) -> Self {
+        match value {
+            KeyModifier::None => KeyModifiers::NONE,
+            KeyModifier::Ctrl => KeyModifiers::CONTROL,
+            KeyModifier::Shift => KeyModifiers::SHIFT,
+            KeyModifier::Alt => KeyModifiers::ALT,
+        }
+    }
+}
+
+fn parse_modifier(modifier: &str) -> Result<KeyModifier, String> {
+    match modifier.to_lowercase().as_str() {
+        "none" => Ok(KeyModifier::None),
+        "ctrl" => Ok(KeyModifier::Ctrl),
+        "shift" => Ok(KeyModifier::Shift),
+        "alt" => Ok(KeyModifier::Alt),
+        _ => Err(format!(
+            "unknown modifier \"{modifier}\", expected one of: none, ctrl, shift, alt"
+        )),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::One(value) => vec![value],
+            Self::Many(values) => values,
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Keybinding<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct RawKeybinding<T> {
+            on: OneOrMany,
+            // Either "ctrl" or a list like ["ctrl", "shift"]
+            modifier: Option<OneOrMany>,
+            action: T,
+        }
+
+        let raw = RawKeybinding::<T>::deserialize(deserializer)?;
+        let keys = raw.on.into_vec();
+        if keys.is_empty() {
+            return Err(de::Error::custom("\"on\" needs at least one key"));
+        }
+
+        let on = keys
+            .iter()
+            .map(|key| parse_keycode(key).map_err(de::Error::custom))
+            .collect::<Result<_, _>>()?;
+
+        let mut modifiers: Vec<KeyModifier> = raw
+            .modifier
+            .map(OneOrMany::into_vec)
+            .unwrap_or_default()
+            .iter()
+            .map(|modifier| parse_modifier(modifier).map_err(de::Error::custom))
+            .collect::<Result<_, _>>()?;
+        modifiers.retain(|modifier| *modifier != KeyModifier::None);
+
+        Ok(Self {
+            on,
+            modifiers,
+            action: raw.action,
+        })
+    }
+}
+
+fn parse_keycode(key: &str) -> Result<KeyCode, String> {
+    let mut chars = key.chars();
+    if let (Some(char), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(char));
+    }
+
+    let keycode = match key.to_lowercase().as_str() {
+        "enter" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "space" => KeyCode::Char(' '),
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" | "ins" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        fkey if fkey.starts_with('f') => fkey[1..]
+            .parse()
+            .ok()
+            .filter(|n| (1..=12).contains(n))
+            .map(KeyCode::F)
+            .ok_or_else(|| format!("unknown key \"{key}\""))?,
+        _ => return Err(format!("unknown key \"{key}\"")),
+    };
+
+    Ok(keycode)
+}
+
+// Terminals report shifted characters already uppercased, with or without
+// the SHIFT modifier, so bindings and key events are compared without it.
+// The same goes for BackTab, which is SHIFT+Tab by definition.
+pub fn normalize_key(keycode: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    match keycode {
+        KeyCode::Char(char) if modifiers.contains(KeyModifiers::SHIFT) => (
+            KeyCode::Char(char.to_ascii_uppercase()),
+            modifiers - KeyModifiers::SHIFT,
+        ),
+        KeyCode::BackTab => (keycode, modifiers - KeyModifiers::SHIFT),
+        keycode => (keycode, modifiers),
+    }
+}
+
+impl<T> Keybinding<T> {
+    // Arrow keys are shown either as glyphs (←) or as words (Left)
+    pub fn keycode_string(&self, arrow_glyphs: bool) -> String {
+        let keys: Vec<_> = self
+            .on
+            .iter()
+            .map(|keycode| {
+                let mut key = String::new();
+                for modifier in &self.modifiers {
+                    key.push_str(modifier.to_str());
+                    key.push('-');
+                }
+                key.push_str(&keycode_to_str(*keycode, arrow_glyphs));
+                key
+            })
+            .collect();
+
+        keys.join(" ")
+    }
+
+    fn key_modifiers(&self) -> KeyModifiers {
+        self.modifiers
+            .iter()
+            .fold(KeyModifiers::NONE, |modifiers, modifier| {
+                modifiers | KeyModifiers::from(*modifier)
+            })
+    }
+
+    fn key_sequence(&self) -> KeySequence {
+        self.on
+            .iter()
+            .map(|keycode| normalize_key(*keycode, self.key_modifiers()))
+            .collect()
+    }
+}
+
+impl<T: std::fmt::Debug> Keybinding<T> {
+    // Keys as matched, keys as shown to the user, and the bound action
+    fn describe(&self) -> (KeySequence, String, String) {
+        (
+            self.key_sequence(),
+            self.keycode_string(false),
+            format!("{:?}", self.action),
+        )
+    }
+}
+
+// Pairs of bindings that can't both work, given as lines of an error
+fn conflicts(bindings: &[(KeySequence, String, String)]) -> Vec<String> {
+    let mut conflicts = vec![];
+    for (idx, (sequence, keys, action)) in bindings.iter().enumerate() {
+        for (other_sequence, other_keys, other_action) in &bindings[idx + 1..] {
+            if sequence == other_sequence {
+                conflicts.push(format!(
+                    "  \"{keys}\" is bound to both {action} and {other_action}"
+                ));
+            } else if other_sequence.starts_with(sequence) {
+                conflicts.push(format!(
+                    "  \"{keys}\" ({action}) shadows \"{other_keys}\" ({other_action})"
+                ));
+            } else if sequence.starts_with(other_sequence) {
+                conflicts.push(format!(
+                    "  \"{other_keys}\" ({other_action}) shadows \"{keys}\" ({action})"
+                ));
+            }
+        }
+    }
+    conflicts
+}
+
+fn keycode_to_str(keycode: KeyCode, arrow_glyphs: bool) -> String {
+    match keycode {
+        KeyCode::Up if !arrow_glyphs => "Up".to_string(),
+        KeyCode::Down if !arrow_glyphs => "Down".to_string(),
+        KeyCode::Left if !arrow_glyphs => "Left".to_string(),
+        KeyCode::Right if !arrow_glyphs => "Right".to_string(),
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(char) => char.to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::BackTab => "ShiftTab".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Delete => "Del".to_string(),
+        KeyCode::Insert => "Ins".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::F(n) => format!("F{n}"),
+        KeyCode::Null => "Null".to_string(),
+        KeyCode::CapsLock => "CapsLock".to_string(),
+        KeyCode::ScrollLock => "ScrollLock".to_string(),
+        KeyCode::NumLock => "NumLock".to_string(),
+        KeyCode::PrintScreen => "PrintScreen".to_string(),
+        KeyCode::Pause => "Pause".to_string(),
+        KeyCode::Menu => "Menu".to_string(),
+        KeyCode::KeypadBegin => "KeypadBegin".to_string(),
+        KeyCode::Media(media) => format!("{media:?}"),
+        KeyCode::Modifier(modifier) => format!("{modifier:?}"),
+    }
+}
+
+impl KeymapConfig {
+    pub fn path() -> Option<PathBuf> {
+        Config::find_config_file("keymap.toml")
+    }
+
+    pub fn init() -> Result<Self> {
+        let Some(keymap_path) = Self::path() else {
+            Self::put_default_keymap_in_home();
+            return Ok(Self::default());
+        };
+
+        // Not being able to read the keymap is no reason to refuse to start
+        let Ok(keymap_buf) = std::fs::read_to_string(&keymap_path) else {
+            return Ok(Self::default());
+        };
+
+        let keymap: Self = toml::from_str(&keymap_buf)
+            .map_err(|e| anyhow!("invalid keymap in {}:\n{e}", keymap_path.display()))?;
+        keymap
+            .validate()
+            .map_err(|e| anyhow!("{e}\nin {}", keymap_path.display()))?;
+        Ok(keymap)
+    }
+
+    // Bindings on the same keys would silently override each other, and a
+    // binding that is the beginning of a longer one makes the latter unreachable.
+    // Only bindings of the same section can clash, a torrents_tab one is
+    // meant to override a general one.
+    pub fn validate(&self) -> Result<()> {
+        let general: Vec<_> = self
+            .general
+            .keybindings
+            .iter()
+            .map(Keybinding::describe)
+            .collect();
+        let torrents_tab: Vec<_> = self
+            .torrents_tab
+            .keybindings
+            .iter()
+            .map(Keybinding::describe)
+            .collect();
+
+        let problems = [conflicts(&general), conflicts(&torrents_tab)].concat();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            bail!("conflicting keybindings:\n{}", problems.join("\n"))
+        }
+    }
+
+    // Gives the user a starting point for their changes. The defaults are
+    // used either way, so a failure here doesn't matter.
+    fn put_default_keymap_in_home() {
+        let Ok(keymap_path) = Config::place_config_file("keymap.toml") else {
+            return;
+        };
+        if let Ok(mut keymap_file) = File::options()
+            .write(true)
+            .create_new(true)
+            .open(keymap_path)
+        {
+            let _ = keymap_file.write_all(DEFAULT_KEYMAP.as_bytes());
+        }
+    }
+
+    // Bindings active in every tab
+    pub fn general_map<A: From<GeneralAction>>(&self) -> HashMap<KeySequence, A> {
+        self.general
+            .keybindings
+            .iter()
+            .map(|keybinding| (keybinding.key_sequence(), keybinding.action.into()))
+            .collect()
+    }
+
+    // Bindings active only in the given tab, consulted before the general ones
+    pub fn to_map_for_tab<A: From<TorrentsAction>>(&self, tab: Tab) -> HashMap<KeySequence, A> {
+        match tab {
+            Tab::Torrents => self
+                .torrents_tab
+                .keybindings
+                .iter()
+                .map(|keybinding| (keybinding.key_sequence(), keybinding.action.into()))
+                .collect(),
+            Tab::Search => HashMap::new(),
+        }
+    }
+}
+
+impl Default for KeymapConfig {
+    fn default() -> Self {
+        toml::from_str(DEFAULT_KEYMAP).expect("default keymap is valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binds_every_action_by_default() {
+        let keymap = KeymapConfig::default();
+        let general: Vec<_> = keymap
+            .general
+            .keybindings
+            .iter()
+            .map(|keybinding| keybinding.action)
+            .collect();
+        let torrents_tab: Vec<_> = keymap
+            .torrents_tab
+            .keybindings
+            .iter()
+            .map(|keybinding| keybinding.action)
+            .collect();
+
+        for action in GeneralAction::ALL {
+            assert!(general.contains(&action), "{action:?} isn't bound");
+        }
+        for action in TorrentsAction::ALL {
+            assert!(torrents_tab.contains(&action), "{action:?} isn't bound");
+        }
+    }
+
+    #[test]
+    fn parses_default_keymap() {
+        let keymap = KeymapConfig::default();
+        assert!(keymap.validate().is_ok());
+
+        let map: HashMap<KeySequence, GeneralAction> = keymap
+            .general
+            .keybindings
+            .iter()
+            .map(|keybinding| (keybinding.key_sequence(), keybinding.action))
+            .collect();
+
+        let go_to_beginning = vec![
+            (KeyCode::Char('g'), KeyModifiers::NONE),
+            (KeyCode::Char('g'), KeyModifiers::NONE),
+        ];
+        assert_eq!(
+            map.get(&go_to_beginning),
+            Some(&GeneralAction::GoToBeginning)
+        );
+    }
+
+    #[test]
+    fn leaves_digits_free_for_counts_by_default() {
+        let keymap = KeymapConfig::default();
+        let sequences = keymap
+            .general
+            .keybindings
+            .iter()
+            .map(Keybinding::key_sequence)
+            .chain(
+                keymap
+                    .torrents_tab
+                    .keybindings
+                    .iter()
+                    .map(Keybinding::key_sequence),
+            );
+
+        for sequence in sequences {
+            assert!(
+                !matches!(
+                    sequence.first(),
+                    Some((KeyCode::Char('0'..='9'), KeyModifiers::NONE))
+                ),
+                "{sequence:?} starts with a bare digit"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_single_and_combined_modifiers() {
+        let holder: KeybindsHolder<GeneralAction> = toml::from_str(
+            r#"
+            keybindings = [
+              { on = "q", modifier = "Ctrl", action = "Quit" },
+              { on = "q", modifier = ["ctrl", "alt"], action = "Quit" },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        let modifiers: Vec<_> = holder
+            .keybindings
+            .iter()
+            .map(Keybinding::key_modifiers)
+            .collect();
+        assert_eq!(
+            modifiers,
+            [
+                KeyModifiers::CONTROL,
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_named_keys() {
+        let holder: KeybindsHolder<GeneralAction> = toml::from_str(
+            r#"
+            keybindings = [
+              { on = "delete", action = "Quit" },
+              { on = "BackTab", action = "Quit" },
+              { on = "Space", modifier = "ctrl", action = "Quit" },
+              { on = ["g", "F5"], action = "Quit" },
+              { on = "Left", action = "Quit" },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        let keys: Vec<_> = holder
+            .keybindings
+            .iter()
+            .map(|keybinding| keybinding.keycode_string(false))
+            .collect();
+        assert_eq!(keys, ["Del", "ShiftTab", "CTRL-Space", "g F5", "Left"]);
+    }
+
+    #[test]
+    fn detects_conflicting_keybindings() {
+        let keymap: KeymapConfig = toml::from_str(
+            r#"
+            [general]
+            keybindings = [
+              { on = "q", action = "Quit" },
+            ]
+
+            [torrents_tab]
+            keybindings = [
+              { on = "d", action = "DeleteWithoutFiles" },
+              { on = ["d", "d"], action = "DeleteWithFiles" },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        assert!(keymap.validate().is_err());
+    }
+
+    #[test]
+    fn lets_torrents_tab_override_general() {
+        let keymap: KeymapConfig = toml::from_str(
+            r#"
+            [general]
+            keybindings = [
+              { on = "d", action = "Quit" },
+              { on = "g", action = "GoToBeginning" },
+            ]
+
+            [torrents_tab]
+            keybindings = [
+              { on = "d", action = "DeleteWithoutFiles" },
+              { on = ["g", "s"], action = "ShowStats" },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        assert!(keymap.validate().is_ok());
+    }
+}
@@ -1,47 +1,453 @@
+mod column_width;
+mod datetime;
+pub mod keymap;
+mod units;
+
 use std::{
+    collections::HashMap,
     fs::File,
-    io::{Read, Write},
+    io::Write,
     path::PathBuf,
-    sync::OnceLock,
+    sync::{OnceLock, RwLock},
+    time::Duration,
 };
 
-use anyhow::{bail, Context, Result};
-use ratatui::style::Color;
-use serde::{Deserialize, Serialize};
-use toml::Table;
+use anyhow::{anyhow, bail, Context, Result};
+use ratatui::style::{Color, Style, Stylize};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use xdg::BaseDirectories;
 
+pub use crate::column_width::ColumnWidth;
+pub use crate::datetime::DateTimeFormat;
+use crate::keymap::{KeymapConfig, Tab};
+pub use crate::units::{SizeUnits, Units};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub connection: Connection,
     pub general: General,
+    #[serde(default)]
+    pub torrents_tab: TorrentsTab,
+    #[serde(default)]
+    pub theme: Theme,
+    #[serde(default)]
+    pub auto_remove: AutoRemove,
+    // Other daemons to switch to, besides the one in [connection]
+    #[serde(default)]
+    pub servers: Vec<Server>,
+    // Lives in its own keymap.toml, and can be reloaded while running
+    #[serde(skip)]
+    pub keymap: RwLock<KeymapConfig>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct General {
     #[serde(default)]
     pub auto_hide: bool,
-    #[serde(default = "default_accent_color")]
+    #[serde(
+        default = "default_accent_color",
+        deserialize_with = "deserialize_color"
+    )]
     pub accent_color: Color,
     #[serde(default = "default_beginner_mode")]
     pub beginner_mode: bool,
     #[serde(default)]
     pub headers_hide: bool,
+    pub default_download_dir: Option<String>,
+    #[serde(default = "default_chord_timeout")]
+    pub chord_timeout: u64,
+    #[serde(default = "default_arrow_glyphs")]
+    pub arrow_glyphs: bool,
+    // Overrides the torrents and stats refresh timings in [connection]
+    pub refresh_interval_ms: Option<u64>,
+    #[serde(default = "default_idle_timeout")]
+    pub idle_timeout: u64,
+    #[serde(default = "default_file_opener")]
+    pub file_opener: String,
+    #[serde(default)]
+    pub speed_graph: SpeedGraph,
+    #[serde(default = "default_speed_graph_length")]
+    pub speed_graph_length: usize,
+    #[serde(default = "default_mouse")]
+    pub mouse: bool,
+    #[serde(default = "default_confirm_actions")]
+    pub confirm_actions: Vec<ConfirmAction>,
+    #[serde(default)]
+    pub units: SizeUnits,
+    #[serde(default)]
+    pub speed_in_bits: bool,
+    #[serde(default)]
+    pub datetime_format: DateTimeFormat,
+    #[serde(default = "default_tabs", deserialize_with = "deserialize_tabs")]
+    pub tabs: Vec<Tab>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeedGraph {
+    Off,
+    Download,
+    Upload,
+    #[default]
+    Both,
+}
+
+// Destructive actions that can be asked about before they're sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmAction {
+    DeleteWithFiles,
+    DeleteWithoutFiles,
+}
+
+fn default_tabs() -> Vec<Tab> {
+    vec![Tab::Torrents, Tab::Search]
+}
+
+fn deserialize_tabs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Tab>, D::Error> {
+    let tabs = Vec::<Tab>::deserialize(deserializer)?;
+    if tabs.is_empty() {
+        return Err(serde::de::Error::custom("at least one tab is needed"));
+    }
+    let is_repeated = |(idx, tab): (usize, &Tab)| tabs[..idx].contains(tab);
+    if tabs.iter().enumerate().any(is_repeated) {
+        return Err(serde::de::Error::custom("a tab can't be listed twice"));
+    }
+    Ok(tabs)
+}
+
+fn default_confirm_actions() -> Vec<ConfirmAction> {
+    vec![ConfirmAction::DeleteWithFiles]
 }
 
 fn default_accent_color() -> Color {
     Color::LightMagenta
 }
 
+// Accepts a name ("LightMagenta"), a hex ("#3cb371") or a 256-color index.
+pub fn parse_color(color: &str) -> Result<Color> {
+    color.parse().map_err(|_| {
+        anyhow!(
+            "invalid color \"{color}\", expected a name like \"LightMagenta\", \
+             a hex like \"#3cb371\" or a 256-color index like \"208\""
+        )
+    })
+}
+
+// The index may also be written without quotes
+fn deserialize_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ColorValue {
+        Index(u8),
+        Text(String),
+    }
+
+    match ColorValue::deserialize(deserializer)? {
+        ColorValue::Index(index) => Ok(Color::Indexed(index)),
+        ColorValue::Text(text) => parse_color(&text).map_err(serde::de::Error::custom),
+    }
+}
+
+fn deserialize_optional_color<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Color>, D::Error> {
+    deserialize_color(deserializer).map(Some)
+}
+
 fn default_beginner_mode() -> bool {
     true
 }
 
+fn default_chord_timeout() -> u64 {
+    1000
+}
+
+fn default_arrow_glyphs() -> bool {
+    true
+}
+
+fn default_idle_timeout() -> u64 {
+    300
+}
+
+fn default_speed_graph_length() -> usize {
+    30
+}
+
+fn default_mouse() -> bool {
+    true
+}
+
+fn default_show_full_name() -> bool {
+    true
+}
+
+fn default_file_opener() -> String {
+    if cfg!(target_os = "macos") {
+        "open".to_string()
+    } else if cfg!(target_os = "windows") {
+        "explorer".to_string()
+    } else {
+        "xdg-open".to_string()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TorrentsTab {
+    #[serde(default = "default_columns")]
+    pub columns: Vec<Header>,
+    // Columns left out keep their default width
+    #[serde(default)]
+    pub widths: HashMap<Header, ColumnWidth>,
+    #[serde(default)]
+    pub filter_mode: FilterMode,
+    #[serde(default)]
+    pub progress_bar: bool,
+    #[serde(default)]
+    pub details_pane: bool,
+    #[serde(default)]
+    pub expanded_rows: bool,
+    #[serde(default)]
+    pub wrap_selection: bool,
+    #[serde(default)]
+    pub show_totals: bool,
+    #[serde(default = "default_show_full_name")]
+    pub show_full_name: bool,
+    #[serde(default)]
+    pub export_format: ExportFormat,
+}
+
+impl TorrentsTab {
+    pub fn column_width(&self, header: Header) -> ColumnWidth {
+        self.widths
+            .get(&header)
+            .copied()
+            .unwrap_or(header.default_width())
+    }
+}
+
+impl Default for TorrentsTab {
+    fn default() -> Self {
+        Self {
+            columns: default_columns(),
+            widths: HashMap::new(),
+            filter_mode: FilterMode::default(),
+            progress_bar: false,
+            details_pane: false,
+            expanded_rows: false,
+            wrap_selection: false,
+            show_totals: false,
+            show_full_name: default_show_full_name(),
+            export_format: ExportFormat::default(),
+        }
+    }
+}
+
+// Removes torrents done downloading, leaving their data. Off unless enabled,
+// and then only once a threshold is reached.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoRemove {
+    pub enabled: bool,
+    pub ratio: Option<f32>,
+    // Without uploading anything
+    pub idle_minutes: Option<u64>,
+}
+
+impl AutoRemove {
+    pub const fn is_active(&self) -> bool {
+        self.enabled && (self.ratio.is_some() || self.idle_minutes.is_some())
+    }
+
+    pub fn is_due(&self, ratio: f32, idle: Duration) -> bool {
+        let ratio_reached = self.ratio.is_some_and(|limit| ratio >= limit);
+        let idle_reached = self
+            .idle_minutes
+            .is_some_and(|minutes| idle >= Duration::from_secs(minutes * 60));
+        self.is_active() && (ratio_reached || idle_reached)
+    }
+}
+
+// Missing entries keep the default look, a given entry replaces it entirely
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub header: ThemeStyle,
+    // Without fg, the accent color is used
+    pub selected_row: ThemeStyle,
+    pub downloading: ThemeStyle,
+    pub seeding: ThemeStyle,
+    pub stopped: ThemeStyle,
+    pub verifying: ThemeStyle,
+    // Applied on top of the status style of torrents with an error
+    pub error: ThemeStyle,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            header: ThemeStyle::default(),
+            selected_row: ThemeStyle {
+                bg: Some(Color::Black),
+                bold: true,
+                ..ThemeStyle::default()
+            },
+            downloading: ThemeStyle::default(),
+            seeding: ThemeStyle::default(),
+            stopped: ThemeStyle {
+                fg: Some(Color::DarkGray),
+                italic: true,
+                ..ThemeStyle::default()
+            },
+            verifying: ThemeStyle {
+                fg: Some(Color::Yellow),
+                ..ThemeStyle::default()
+            },
+            error: ThemeStyle {
+                fg: Some(Color::Red),
+                ..ThemeStyle::default()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeStyle {
+    #[serde(deserialize_with = "deserialize_optional_color")]
+    pub fg: Option<Color>,
+    #[serde(deserialize_with = "deserialize_optional_color")]
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+impl ThemeStyle {
+    pub fn to_style(self) -> Style {
+        let mut style = Style::default();
+        if let Some(fg) = self.fg {
+            style = style.fg(fg);
+        }
+        if let Some(bg) = self.bg {
+            style = style.bg(bg);
+        }
+        if self.bold {
+            style = style.bold();
+        }
+        if self.italic {
+            style = style.italic();
+        }
+        style
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterMode {
+    Substring,
+    #[default]
+    Fuzzy,
+    Regex,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+fn default_columns() -> Vec<Header> {
+    vec![
+        Header::Name,
+        Header::Size,
+        Header::Progress,
+        Header::Eta,
+        Header::Download,
+        Header::Upload,
+        Header::Ratio,
+    ]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Header {
+    Name,
+    Size,
+    Progress,
+    Eta,
+    Download,
+    Upload,
+    Ratio,
+    Queue,
+    Labels,
+    Status,
+    Peers,
+}
+
+impl Header {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Size => "Size",
+            Self::Progress => "Progress",
+            Self::Eta => "ETA",
+            Self::Download => "Download",
+            Self::Upload => "Upload",
+            Self::Ratio => "Ratio",
+            Self::Queue => "Queue",
+            Self::Labels => "Labels",
+            Self::Status => "Status",
+            Self::Peers => "Peers",
+        }
+    }
+
+    pub const fn default_width(self) -> ColumnWidth {
+        match self {
+            Self::Name => ColumnWidth::Range {
+                min: 0,
+                max: Some(70),
+            },
+            Self::Size => ColumnWidth::Length(12),
+            Self::Progress => ColumnWidth::Length(12),
+            Self::Eta => ColumnWidth::Length(12),
+            Self::Download => ColumnWidth::Length(12),
+            Self::Upload => ColumnWidth::Length(12),
+            Self::Ratio => ColumnWidth::Length(8),
+            Self::Queue => ColumnWidth::Length(6),
+            Self::Labels => ColumnWidth::Range {
+                min: 0,
+                max: Some(20),
+            },
+            Self::Status => ColumnWidth::Length(15),
+            Self::Peers => ColumnWidth::Length(10),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Connection {
+    #[serde(default = "default_server_name")]
+    pub name: String,
     pub username: Option<String>,
     pub password: Option<String>,
+    // Its output is used as the password instead
+    pub password_cmd: Option<String>,
     pub url: String,
+    // For self-signed certificates, skips verifying them altogether
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
     #[serde(default)]
     pub torrents_refresh: u64,
     #[serde(default)]
@@ -50,94 +456,436 @@ pub struct Connection {
     pub free_space_refresh: u64,
 }
 
-const DEFAULT_CONFIG: &str = include_str!("../defaults/config.toml");
+fn default_server_name() -> String {
+    "default".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Server {
+    pub name: String,
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub password_cmd: Option<String>,
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+}
+
+impl Server {
+    // Runs password_cmd if given, like git's credential helpers
+    pub fn password(&self) -> Result<Option<String>> {
+        let Some(password_cmd) = &self.password_cmd else {
+            return Ok(self.password.clone());
+        };
+
+        let (shell, flag) = if cfg!(target_os = "windows") {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        let output = std::process::Command::new(shell)
+            .args([flag, password_cmd])
+            .stdin(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .output()
+            .with_context(|| format!("failed to run password_cmd '{password_cmd}'"))?;
+        if !output.status.success() {
+            bail!(
+                "password_cmd '{password_cmd}' of server '{}' failed with {}",
+                self.name,
+                output.status
+            );
+        }
+
+        let password = String::from_utf8(output.stdout)
+            .with_context(|| format!("password_cmd '{password_cmd}' printed invalid UTF-8"))?;
+        let password = password.trim_end_matches(['\n', '\r']);
+        if password.is_empty() {
+            bail!(
+                "password_cmd '{password_cmd}' of server '{}' printed nothing",
+                self.name
+            );
+        }
+        Ok(Some(password.to_string()))
+    }
+}
+
+const URL_ENV: &str = "TRANSMISSION_URL";
+const USER_ENV: &str = "TRANSMISSION_USER";
+const PASS_ENV: &str = "TRANSMISSION_PASS";
+
+impl Connection {
+    // Environment variables take precedence over config.toml
+    fn override_from_env(&mut self) -> Result<()> {
+        self.override_with(
+            std::env::var(URL_ENV).ok(),
+            std::env::var(USER_ENV).ok(),
+            std::env::var(PASS_ENV).ok(),
+        )
+    }
+
+    fn override_with(
+        &mut self,
+        url: Option<String>,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> Result<()> {
+        if let Some(url) = url {
+            verify_url(&url).with_context(|| format!("in {URL_ENV}"))?;
+            self.url = url;
+        }
+        if username.is_some() {
+            self.username = username;
+        }
+        if password.is_some() {
+            self.password = password;
+            self.password_cmd = None;
+        }
+        Ok(())
+    }
+}
+
+pub const DEFAULT_CONFIG: &str = include_str!("../defaults/config.toml");
 static XDG_DIRS: OnceLock<BaseDirectories> = OnceLock::new();
+// Given with --config-dir, replaces the XDG config directory
+static CONFIG_DIR: OnceLock<PathBuf> = OnceLock::new();
 static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+static STATE_PATH: OnceLock<PathBuf> = OnceLock::new();
+// Anything lower would just hammer the daemon
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_millis(200);
 
 impl Config {
     pub fn init() -> Result<Self> {
-        let Ok(table) = Self::table_from_home() else {
-            Self::put_default_conf_in_home()?;
-            // TODO: check if the user really changed the config.
-            println!(
-                "Update {:?} and start rustmission again",
-                Self::get_config_path()
-            );
-            std::process::exit(0);
+        // On the first run the defaults are written out and loaded, which
+        // also puts keymap.toml next to config.toml
+        let (config_path, first_run) = match Self::find_config_file("config.toml") {
+            Some(config_path) => (config_path, false),
+            None => (Self::put_default_conf_in_home()?, true),
         };
+        let text = std::fs::read_to_string(&config_path)
+            .with_context(|| format!("can't read {}", config_path.display()))?;
 
-        Self::table_config_verify(&table)?;
+        // The keymap is checked even when config.toml is broken, so that
+        // everything can be fixed in one go
+        let mut problems = vec![];
+        let config = Self::parse(&text)
+            .map_err(|config_problems| {
+                problems.extend(
+                    config_problems
+                        .into_iter()
+                        .map(|problem| format!("{}:{problem}", config_path.display())),
+                );
+            })
+            .ok();
+        let keymap = KeymapConfig::init()
+            .map_err(|e| problems.push(e.to_string()))
+            .ok();
 
-        Self::table_to_config(&table)
-    }
-
-    fn table_from_home() -> Result<Table> {
-        let xdg_dirs = xdg::BaseDirectories::with_prefix("rustmission")?;
-        let config_path = xdg_dirs
-            .find_config_file("config.toml")
-            .ok_or_else(|| anyhow::anyhow!("config.toml not found"))?;
-
-        let mut config_buf = String::new();
-        let mut config_file = File::open(config_path).unwrap();
-        config_file.read_to_string(&mut config_buf).unwrap();
-        Ok(toml::from_str(&config_buf)?)
+        match (config, keymap) {
+            (Some(mut config), Some(keymap)) if problems.is_empty() => {
+                // There's nothing to connect to until the url is filled in,
+                // unless it was given through the environment
+                if first_run && config.connection.url.contains("CHANGE_ME") {
+                    println!(
+                        "Wrote the default config to {config_path:?} and the keymap next to it. \
+                         Set the url in [connection] and start rustmission again"
+                    );
+                    std::process::exit(0);
+                }
+                config.keymap = RwLock::new(keymap);
+                Ok(config)
+            }
+            _ => bail!(
+                "found {} problem(s) in the configuration:\n{}",
+                problems.len(),
+                problems
+                    .iter()
+                    .map(|problem| format!("- {}", problem.trim_end().replace('\n', "\n  ")))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+        }
     }
 
-    fn put_default_conf_in_home() -> Result<Table> {
+    // Never replaces a config that's already there
+    fn put_default_conf_in_home() -> Result<PathBuf> {
         let config_path = Self::get_config_path();
-        let mut config_file = File::create(config_path)?;
+        let mut config_file = File::options()
+            .write(true)
+            .create_new(true)
+            .open(config_path)
+            .with_context(|| format!("can't create {}", config_path.display()))?;
         config_file.write_all(DEFAULT_CONFIG.as_bytes())?;
-        Ok(toml::from_str(DEFAULT_CONFIG)?)
+        Ok(config_path.clone())
     }
 
-    fn table_to_config(table: &Table) -> Result<Self> {
-        let config_string = table.to_string();
-        let config: Self = toml::from_str(&config_string)?;
-        Ok(config)
+    // Every section is deserialized on its own so that a mistake in one
+    // doesn't hide the ones in the others. Problems start with where they're
+    // at, as "line:column: ".
+    fn parse(text: &str) -> Result<Self, Vec<String>> {
+        let raw: RawConfig = toml::from_str(text).map_err(|e| vec![format!("\n{e}")])?;
+        let mut problems = Problems::new(text);
+
+        let connection = problems.required_section::<Connection>("connection", raw.connection);
+        let general = problems.required_section::<General>("general", raw.general);
+        let torrents_tab = problems.section("torrents_tab", raw.torrents_tab);
+        let theme = problems.section("theme", raw.theme);
+        let auto_remove = problems.section("auto_remove", raw.auto_remove);
+
+        let mut servers = vec![];
+        for (idx, server) in raw.servers.unwrap_or_default().into_iter().enumerate() {
+            let offset = problems.header_offset("[[servers]]", idx);
+            if let Some(server) = problems.value::<Server>("[[servers]]", server, offset) {
+                if let Err(e) = verify_url(&server.url) {
+                    let section = format!("[[servers]] '{}'", server.name);
+                    problems.push(offset, &section, &e);
+                }
+                servers.push(server);
+            }
+        }
+
+        let connection = connection.map(|mut connection| {
+            let offset = problems.header_offset("[connection]", 0);
+            if let Err(e) = verify_url(&connection.url) {
+                problems.push(offset, "[connection]", &e);
+            }
+            if let Err(e) = connection.override_from_env() {
+                problems.push(offset, "[connection]", &e);
+            }
+            connection
+        });
+
+        match (connection, general, torrents_tab, theme, auto_remove) {
+            (
+                Some(connection),
+                Some(general),
+                Some(torrents_tab),
+                Some(theme),
+                Some(auto_remove),
+            ) if problems.list.is_empty() => Ok(Self {
+                connection,
+                general,
+                torrents_tab,
+                theme,
+                auto_remove,
+                servers,
+                keymap: RwLock::default(),
+            }),
+            _ => Err(problems.list),
+        }
     }
 
-    fn table_config_verify(table: &Table) -> Result<()> {
-        let Some(connection_table) = table.get("connection").unwrap().as_table() else {
-            bail!("expected connection table")
+    // The one in [connection] comes first
+    pub fn servers(&self) -> Vec<Server> {
+        let connection = &self.connection;
+        let main_server = Server {
+            name: connection.name.clone(),
+            url: connection.url.clone(),
+            username: connection.username.clone(),
+            password: connection.password.clone(),
+            password_cmd: connection.password_cmd.clone(),
+            accept_invalid_certs: connection.accept_invalid_certs,
         };
+        std::iter::once(main_server)
+            .chain(self.servers.iter().cloned())
+            .collect()
+    }
 
-        let url = connection_table
-            .get("url")
-            .and_then(|url| url.as_str())
-            .with_context(|| {
-                format!(
-                    "no url given in: {}",
-                    Self::get_config_path().to_str().unwrap()
-                )
-            })?;
-
-        url::Url::parse(url).with_context(|| {
-            format!(
-                "invalid url '{url}' in {}",
-                Self::get_config_path().to_str().unwrap()
-            )
-        })?;
+    pub fn torrents_refresh(&self) -> Duration {
+        self.refresh_interval(self.connection.torrents_refresh)
+    }
 
-        Ok(())
+    pub fn stats_refresh(&self) -> Duration {
+        self.refresh_interval(self.connection.stats_refresh)
+    }
+
+    pub fn free_space_refresh(&self) -> Duration {
+        Duration::from_secs(self.connection.free_space_refresh).max(MIN_REFRESH_INTERVAL)
+    }
+
+    pub fn units(&self) -> Units {
+        Units {
+            size: self.general.units,
+            speed_in_bits: self.general.speed_in_bits,
+        }
+    }
+
+    pub fn confirms(&self, action: ConfirmAction) -> bool {
+        self.general.confirm_actions.contains(&action)
+    }
+
+    // None if fetching should never pause
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        match self.general.idle_timeout {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    fn refresh_interval(&self, secs: u64) -> Duration {
+        self.general
+            .refresh_interval_ms
+            .map_or(Duration::from_secs(secs), Duration::from_millis)
+            .max(MIN_REFRESH_INTERVAL)
     }
 
     fn get_xdg_dirs() -> &'static BaseDirectories {
         XDG_DIRS.get_or_init(|| xdg::BaseDirectories::with_prefix("rustmission").unwrap())
     }
 
+    // Has to be called before anything is loaded, the paths are cached from then on
+    pub fn set_config_dir(dir: PathBuf) -> Result<()> {
+        if CONFIG_PATH.get().is_some() || STATE_PATH.get().is_some() {
+            bail!("the config directory can't be changed once it's in use");
+        }
+        CONFIG_DIR
+            .set(dir)
+            .map_err(|_| anyhow!("the config directory was already set"))
+    }
+
+    // Without --config-dir, the XDG config directories are searched as usual
+    pub(crate) fn find_config_file(name: &str) -> Option<PathBuf> {
+        match CONFIG_DIR.get() {
+            Some(dir) => Some(dir.join(name)).filter(|path| path.is_file()),
+            None => Self::get_xdg_dirs().find_config_file(name),
+        }
+    }
+
+    // Creates the directory if it doesn't exist yet
+    pub(crate) fn place_config_file(name: &str) -> std::io::Result<PathBuf> {
+        match CONFIG_DIR.get() {
+            Some(dir) => {
+                std::fs::create_dir_all(dir)?;
+                Ok(dir.join(name))
+            }
+            None => Self::get_xdg_dirs().place_config_file(name),
+        }
+    }
+
     pub fn get_config_path() -> &'static PathBuf {
-        CONFIG_PATH.get_or_init(|| {
-            Self::get_xdg_dirs()
-                .place_config_file("config.toml")
-                .unwrap()
-        })
+        CONFIG_PATH.get_or_init(|| Self::place_config_file("config.toml").unwrap())
+    }
+
+    // Not meant to be edited by the user, unlike config.toml
+    pub fn get_state_path() -> &'static PathBuf {
+        STATE_PATH.get_or_init(|| Self::place_config_file("state.toml").unwrap())
+    }
+
+    // Overwritten on every export, so scripts can rely on the name
+    pub fn get_export_path(format: ExportFormat) -> std::io::Result<PathBuf> {
+        Self::place_config_file(&format!("torrents.{}", format.extension()))
+    }
+}
+
+#[derive(Deserialize)]
+struct RawConfig {
+    connection: Option<toml::Value>,
+    general: Option<toml::Value>,
+    torrents_tab: Option<toml::Value>,
+    theme: Option<toml::Value>,
+    auto_remove: Option<toml::Value>,
+    servers: Option<Vec<toml::Value>>,
+}
+
+struct Problems<'a> {
+    text: &'a str,
+    list: Vec<String>,
+}
+
+impl<'a> Problems<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, list: vec![] }
+    }
+
+    // toml's spans don't survive tables like [theme.stopped], so the
+    // header is looked up by hand. Nested headers count for their parent.
+    fn header_offset(&self, header: &str, nth: usize) -> usize {
+        let nested = format!("{}.", header.trim_end_matches(']'));
+        let mut offset = 0;
+        self.text
+            .split_inclusive('\n')
+            .filter_map(|line| {
+                let line_offset = offset;
+                offset += line.len();
+                let trimmed = line.trim_start();
+                (trimmed.starts_with(header) || trimmed.starts_with(&nested))
+                    .then(|| line_offset + line.len() - trimmed.len())
+            })
+            .nth(nth)
+            .unwrap_or(0)
+    }
+
+    fn push(&mut self, offset: usize, section: &str, error: &dyn std::fmt::Display) {
+        let before = &self.text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |idx| idx + 1) + 1;
+        self.list
+            .push(format!("{line}:{column}: in {section}: {error:#}"));
+    }
+
+    fn value<T: DeserializeOwned>(
+        &mut self,
+        section: &str,
+        value: toml::Value,
+        offset: usize,
+    ) -> Option<T> {
+        T::deserialize(value)
+            .map_err(|e| self.push(offset, section, &e.message()))
+            .ok()
+    }
+
+    fn required_section<T: DeserializeOwned>(
+        &mut self,
+        name: &str,
+        value: Option<toml::Value>,
+    ) -> Option<T> {
+        let Some(value) = value else {
+            self.list
+                .push(format!("1:1: the [{name}] section is missing"));
+            return None;
+        };
+        let header = format!("[{name}]");
+        let offset = self.header_offset(&header, 0);
+        self.value(&header, value, offset)
+    }
+
+    // A missing optional section gets the defaults
+    fn section<T: DeserializeOwned + Default>(
+        &mut self,
+        name: &str,
+        value: Option<toml::Value>,
+    ) -> Option<T> {
+        match value {
+            Some(_) => self.required_section(name, value),
+            None => Some(T::default()),
+        }
+    }
+}
+
+// Any path is fine, the client posts to the url as given. Only http(s) can be
+// spoken to though.
+fn verify_url(url: &str) -> Result<()> {
+    let parsed = url::Url::parse(url).with_context(|| format!("invalid url '{url}'"))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(()),
+        "unix" => bail!(
+            "'{url}': connecting through a Unix socket is not supported, \
+            expose the RPC over http instead (e.g. with a reverse proxy)"
+        ),
+        scheme => bail!("'{url}': unsupported scheme '{scheme}', expected http or https"),
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use toml::Table;
+
     use super::*;
 
+    fn parse(table: Table) -> Result<Config, Vec<String>> {
+        Config::parse(&table.to_string())
+    }
+
     fn invalid_config() -> Table {
         toml::toml! {
             [connection]
@@ -150,6 +898,8 @@ mod tests {
 
     fn valid_config() -> Table {
         toml::toml! {
+            [general]
+
             [connection]
             username = "username"
             password = "password"
@@ -157,15 +907,282 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_default_config() {
+        assert!(Config::parse(DEFAULT_CONFIG).is_ok());
+    }
+
     #[test]
     fn validates_properly() {
         let valid_config = valid_config();
-        assert!(Config::table_config_verify(&valid_config).is_ok());
+        assert!(parse(valid_config).is_ok());
     }
 
     #[test]
     fn invalidates_properly() {
         let invalid_config = invalid_config();
-        assert!(Config::table_config_verify(&invalid_config).is_err());
+        assert!(parse(invalid_config).is_err());
+    }
+
+    #[test]
+    fn reports_all_problems_at_once() {
+        let problems = Config::parse(
+            r#"
+[general]
+accent_color = "purplish"
+
+[connection]
+url = "bad_url"
+
+[theme.stopped]
+fg = "greyish"
+
+[[servers]]
+name = "seedbox"
+url = "ftp://seedbox.example.com/transmission/rpc"
+"#,
+        )
+        .unwrap_err();
+
+        assert_eq!(problems.len(), 4, "{problems:?}");
+        assert!(problems[0].starts_with("2:1: in [general]: invalid color"));
+        assert!(problems[1].starts_with("8:1: in [theme]: invalid color"));
+        assert!(problems[2].starts_with("11:1: in [[servers]] 'seedbox': "));
+        assert!(problems[3].starts_with("5:1: in [connection]: invalid url"));
+
+        let problems = Config::parse("[general]").unwrap_err();
+        assert_eq!(problems, ["1:1: the [connection] section is missing"]);
+    }
+
+    #[test]
+    fn verifies_url_scheme() {
+        assert!(verify_url("https://example.com/custom/rpc/path").is_ok());
+        assert!(verify_url("http://192.168.1.1:9091/transmission/rpc").is_ok());
+        assert!(verify_url("unix:///run/transmission.sock").is_err());
+        assert!(verify_url("ftp://192.168.1.1/transmission/rpc").is_err());
+    }
+
+    #[test]
+    fn clamps_refresh_interval() {
+        let mut config = parse(toml::toml! {
+            [general]
+            refresh_interval_ms = 50
+
+            [connection]
+            url = "http://192.168.1.1/transmission/rpc"
+            torrents_refresh = 5
+        })
+        .unwrap();
+        assert_eq!(config.torrents_refresh(), MIN_REFRESH_INTERVAL);
+        assert_eq!(config.stats_refresh(), MIN_REFRESH_INTERVAL);
+
+        config.general.refresh_interval_ms = None;
+        assert_eq!(config.torrents_refresh(), Duration::from_secs(5));
+        assert_eq!(config.stats_refresh(), MIN_REFRESH_INTERVAL);
+    }
+
+    #[test]
+    fn overrides_connection_from_env() {
+        let connection = &mut Connection {
+            name: default_server_name(),
+            username: Some("username".into()),
+            password: None,
+            password_cmd: None,
+            url: "http://192.168.1.1/transmission/rpc".into(),
+            accept_invalid_certs: false,
+            torrents_refresh: 5,
+            stats_refresh: 10,
+            free_space_refresh: 10,
+        };
+
+        connection
+            .override_with(
+                Some("http://10.0.0.1:9091/transmission/rpc".into()),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(connection.url, "http://10.0.0.1:9091/transmission/rpc");
+        assert_eq!(connection.username.as_deref(), Some("username"));
+
+        connection
+            .override_with(None, Some("user".into()), Some("pass".into()))
+            .unwrap();
+        assert_eq!(connection.username.as_deref(), Some("user"));
+        assert_eq!(connection.password.as_deref(), Some("pass"));
+
+        let error = connection
+            .override_with(Some("bad_url".into()), None, None)
+            .unwrap_err();
+        assert!(error.to_string().contains(URL_ENV));
+        assert_eq!(connection.url, "http://10.0.0.1:9091/transmission/rpc");
+    }
+
+    #[test]
+    fn lists_connection_first_among_servers() {
+        let config = parse(toml::toml! {
+            [general]
+
+            [connection]
+            url = "http://192.168.1.1/transmission/rpc"
+
+            [[servers]]
+            name = "seedbox"
+            url = "https://seedbox.example.com/transmission/rpc"
+            username = "user"
+        })
+        .unwrap();
+
+        let names: Vec<_> = config
+            .servers()
+            .into_iter()
+            .map(|server| server.name)
+            .collect();
+        assert_eq!(names, ["default", "seedbox"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_password_cmd() {
+        let server = |password_cmd: &str| Server {
+            name: "default".into(),
+            url: "http://192.168.1.1/transmission/rpc".into(),
+            username: None,
+            password: Some("from config".into()),
+            password_cmd: Some(password_cmd.into()),
+            accept_invalid_certs: false,
+        };
+
+        assert_eq!(
+            server("echo 'pass word'").password().unwrap().as_deref(),
+            Some("pass word")
+        );
+        assert!(server("exit 1").password().is_err());
+        assert!(server("printf ''").password().is_err());
+    }
+
+    #[test]
+    fn parses_colors() {
+        assert_eq!(parse_color("LightMagenta").unwrap(), Color::LightMagenta);
+        assert_eq!(parse_color("light-magenta").unwrap(), Color::LightMagenta);
+        assert_eq!(
+            parse_color("#3cb371").unwrap(),
+            Color::Rgb(0x3c, 0xb3, 0x71)
+        );
+        assert_eq!(parse_color("208").unwrap(), Color::Indexed(208));
+        assert!(parse_color("#3cb3").is_err());
+        assert!(parse_color("not a color").is_err());
+    }
+
+    #[test]
+    fn deserializes_accent_color() {
+        let config = parse(toml::toml! {
+            [general]
+            accent_color = 42
+
+            [connection]
+            url = "http://192.168.1.1/transmission/rpc"
+        })
+        .unwrap();
+        assert_eq!(config.general.accent_color, Color::Indexed(42));
+
+        let error = parse(toml::toml! {
+            [general]
+            accent_color = "purplish"
+
+            [connection]
+            url = "http://192.168.1.1/transmission/rpc"
+        })
+        .err()
+        .unwrap();
+        assert!(error[0].contains("invalid color \"purplish\""));
+    }
+
+    #[test]
+    fn deserializes_confirm_actions() {
+        let config = parse(valid_config()).unwrap();
+        assert!(config.confirms(ConfirmAction::DeleteWithFiles));
+        assert!(!config.confirms(ConfirmAction::DeleteWithoutFiles));
+
+        let config = parse(toml::toml! {
+            [general]
+            confirm_actions = []
+
+            [connection]
+            url = "http://192.168.1.1/transmission/rpc"
+        })
+        .unwrap();
+        assert!(!config.confirms(ConfirmAction::DeleteWithFiles));
+    }
+
+    #[test]
+    fn fills_in_theme_defaults() {
+        let config = parse(toml::toml! {
+            [general]
+
+            [connection]
+            url = "http://192.168.1.1/transmission/rpc"
+
+            [theme]
+            stopped = { fg = "#808080" }
+        })
+        .unwrap();
+        let theme = config.theme;
+        assert_eq!(
+            theme.stopped,
+            ThemeStyle {
+                fg: Some(Color::Rgb(0x80, 0x80, 0x80)),
+                ..ThemeStyle::default()
+            }
+        );
+        assert_eq!(theme.selected_row, Theme::default().selected_row);
+        assert_eq!(
+            theme.selected_row.to_style(),
+            Style::default().bg(Color::Black).bold()
+        );
+    }
+
+    #[test]
+    fn validates_tabs() {
+        let parse_tabs = |tabs: &str| {
+            Config::parse(&format!(
+                "[general]\ntabs = {tabs}\n[connection]\nurl = \"http://192.168.1.1/transmission/rpc\""
+            ))
+            .map(|config| config.general.tabs)
+        };
+        assert_eq!(parse_tabs("[\"search\"]").unwrap(), [Tab::Search]);
+        assert!(parse_tabs("[]").is_err());
+        assert!(parse_tabs("[\"torrents\", \"torrents\"]").is_err());
+        assert!(parse_tabs("[\"trackers\"]").is_err());
+    }
+
+    #[test]
+    fn auto_removes_only_when_enabled() {
+        let config = parse(toml::toml! {
+            [general]
+
+            [connection]
+            url = "http://192.168.1.1/transmission/rpc"
+
+            [auto_remove]
+            ratio = 2.0
+        })
+        .unwrap();
+        assert!(!config.auto_remove.is_due(3.0, Duration::ZERO));
+
+        let auto_remove = AutoRemove {
+            enabled: true,
+            ratio: Some(2.0),
+            idle_minutes: Some(60),
+        };
+        assert!(auto_remove.is_due(2.0, Duration::ZERO));
+        assert!(auto_remove.is_due(0.5, Duration::from_secs(60 * 60)));
+        assert!(!auto_remove.is_due(1.9, Duration::from_secs(59 * 60)));
+
+        let without_thresholds = AutoRemove {
+            enabled: true,
+            ..AutoRemove::default()
+        };
+        assert!(!without_thresholds.is_active());
     }
 }
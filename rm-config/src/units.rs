@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeUnits {
+    // Powers of 1024 with the labels of the powers of 1000: KB, MB... as
+    // sizes have always been shown
+    #[default]
+    Classic,
+    // Powers of 1024: KiB, MiB...
+    Binary,
+    // Powers of 1000: kB, MB...
+    Decimal,
+}
+
+impl SizeUnits {
+    const fn base(self) -> f64 {
+        match self {
+            Self::Classic | Self::Binary => 1024.0,
+            Self::Decimal => 1000.0,
+        }
+    }
+
+    const fn byte_suffixes(self) -> [&'static str; 5] {
+        match self {
+            Self::Classic => ["B", "KB", "MB", "GB", "TB"],
+            Self::Binary => ["B", "KiB", "MiB", "GiB", "TiB"],
+            Self::Decimal => ["B", "kB", "MB", "GB", "TB"],
+        }
+    }
+
+    const fn bit_suffixes(self) -> [&'static str; 5] {
+        match self {
+            Self::Classic => ["b", "Kb", "Mb", "Gb", "Tb"],
+            Self::Binary => ["b", "Kib", "Mib", "Gib", "Tib"],
+            Self::Decimal => ["b", "kb", "Mb", "Gb", "Tb"],
+        }
+    }
+}
+
+// How sizes and speeds are shown everywhere, taken from [general]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Units {
+    pub size: SizeUnits,
+    pub speed_in_bits: bool,
+}
+
+impl Units {
+    pub fn size(self, bytes: i64) -> String {
+        format(bytes, self.size.base(), self.size.byte_suffixes())
+    }
+
+    // Per second, though that's left out to keep the columns narrow
+    pub fn speed(self, bytes: i64) -> String {
+        if self.speed_in_bits {
+            format(
+                bytes.saturating_mul(8),
+                self.size.base(),
+                self.size.bit_suffixes(),
+            )
+        } else {
+            self.size(bytes)
+        }
+    }
+}
+
+fn format(value: i64, base: f64, suffixes: [&str; 5]) -> String {
+    if value == 0 {
+        return format!("0 {}", suffixes[0]);
+    }
+
+    // Switches a bit early, so that e.g. 1000 B reads 1.0 KiB rather than 1000.0 B
+    let mut unit = 1f64;
+    let mut suffix = suffixes[0];
+    for next_suffix in &suffixes[1..] {
+        if value < (unit * base - 25f64) as i64 {
+            break;
+        }
+        unit *= base;
+        suffix = next_suffix;
+    }
+
+    format!("{:.1} {suffix}", value as f64 / unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_sizes_as_before_by_default() {
+        let classic = Units::default();
+        assert_eq!(classic.size(0), "0 B");
+        assert_eq!(classic.size(998), "998.0 B");
+        assert_eq!(classic.size(999), "1.0 KB");
+        assert_eq!(classic.size(1536), "1.5 KB");
+        assert_eq!(classic.size(3 * 1024 * 1024), "3.0 MB");
+        assert_eq!(classic.size(5 * 1024 * 1024 * 1024), "5.0 GB");
+        assert_eq!(classic.size(2 * 1024_i64.pow(4)), "2.0 TB");
+        assert_eq!(classic.speed(1536), "1.5 KB");
+    }
+
+    #[test]
+    fn formats_sizes() {
+        let binary = Units {
+            size: SizeUnits::Binary,
+            speed_in_bits: false,
+        };
+        assert_eq!(binary.size(0), "0 B");
+        assert_eq!(binary.size(998), "998.0 B");
+        assert_eq!(binary.size(1536), "1.5 KiB");
+        assert_eq!(binary.size(5 * 1024 * 1024 * 1024), "5.0 GiB");
+
+        let decimal = Units {
+            size: SizeUnits::Decimal,
+            speed_in_bits: false,
+        };
+        assert_eq!(decimal.size(1500), "1.5 kB");
+        assert_eq!(decimal.size(2_000_000), "2.0 MB");
+    }
+
+    #[test]
+    fn formats_speeds_in_bits() {
+        let units = Units {
+            size: SizeUnits::Decimal,
+            speed_in_bits: true,
+        };
+        assert_eq!(units.speed(125_000), "1.0 Mb");
+        assert_eq!(units.size(125_000), "125.0 kB");
+    }
+}
@@ -1,6 +1,18 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
-use crate::{tui::Event, ui::global_popups::ErrorPopup};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use rm_config::{
+    keymap::{normalize_key, GeneralAction, KeySequence, Tab, TorrentsAction},
+    Config,
+};
+
+use crate::{
+    tui::Event,
+    ui::{components::tabs::CurrentTab, global_popups::ErrorPopup},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum Action {
@@ -11,6 +23,8 @@ pub(crate) enum Action {
     Tick,
     Up,
     Down,
+    SelectUp,
+    SelectDown,
     Left,
     Right,
     ScrollDownPage,
@@ -22,16 +36,66 @@ pub(crate) enum Action {
     ShowHelp,
     ShowStats,
     ShowFiles,
+    ShowInfo,
+    ToggleDetails,
+    ToggleRowHeight,
+    GoToRow,
+    SpeedLimit,
+    GlobalSpeedLimit,
+    SeedLimits,
     Search,
     Pause,
+    StartNow,
+    StartAll,
+    StopAll,
+    SortBy,
+    ToggleSortOrder,
+    CycleStatusFilter,
+    ToggleFinished,
+    ToggleAltSpeed,
+    CopyMagnet,
+    OpenDownloadDir,
+    Export,
+    CyclePriority,
+    QueueUp,
+    QueueDown,
+    QueueTop,
+    QueueBottom,
+    EditLabels,
+    EditTrackers,
+    Rename,
     DeleteWithoutFiles,
     DeleteWithFiles,
+    UndoDelete,
+    Move,
+    SetLocation,
+    Verify,
+    Reannounce,
+    ReloadConfig,
+    Refresh,
+    SwitchServer,
+    ServerSwitched,
+    // The torrents table got new data, sent instead of a Render
+    TorrentsFetched,
     SwitchToInputMode,
     SwitchToNormalMode,
     ChangeFocus,
     AddMagnet,
+    // Counting from 1, in the order of general.tabs
     ChangeTab(u8),
+    // Only in the keymap, where it's turned into a ChangeTab
+    SwitchToTab(Tab),
     Input(KeyEvent),
+    Mouse(MouseEvent),
+    Paste(String),
+    ShowStatus(String),
+    // A movement with a count typed before it, like 5j
+    Repeat(usize, Box<Action>),
+    // Sent by the action handler around requests that can take a while
+    TaskStarted(String),
+    TaskFinished(Result<(), String>),
+    // Like a successful TaskFinished, but says what was done
+    TaskDone(String),
     Error(Box<ErrorPopup>),
 }
 
@@ -47,6 +111,13 @@ impl Action {
     pub fn is_soft_quit(&self) -> bool {
         self.is_quit() || *self == Self::SoftQuit
     }
+
+    const fn takes_count(&self) -> bool {
+        matches!(
+            self,
+            Self::Up | Self::Down | Self::ScrollUpPage | Self::ScrollDownPage
+        )
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -55,7 +126,220 @@ pub enum Mode {
     Normal,
 }
 
-pub fn event_to_action(mode: Mode, event: Event) -> Option<Action> {
+impl From<GeneralAction> for Action {
+    fn from(action: GeneralAction) -> Self {
+        use GeneralAction as GA;
+        match action {
+            GA::ShowHelp => Self::ShowHelp,
+            GA::Quit => Self::Quit,
+            GA::SoftQuit => Self::SoftQuit,
+            GA::SwitchToTorrents => Self::SwitchToTab(Tab::Torrents),
+            GA::SwitchToSearch => Self::SwitchToTab(Tab::Search),
+            GA::Left => Self::Left,
+            GA::Right => Self::Right,
+            GA::Down => Self::Down,
+            GA::Up => Self::Up,
+            GA::Search => Self::Search,
+            GA::ChangeFocus => Self::ChangeFocus,
+            GA::Confirm => Self::Confirm,
+            GA::Select => Self::Space,
+            GA::ScrollPageDown => Self::ScrollDownPage,
+            GA::ScrollPageUp => Self::ScrollUpPage,
+            GA::GoToBeginning => Self::Home,
+            GA::GoToEnd => Self::End,
+            GA::ReloadConfig => Self::ReloadConfig,
+            GA::Refresh => Self::Refresh,
+            GA::SwitchServer => Self::SwitchServer,
+        }
+    }
+}
+
+impl From<TorrentsAction> for Action {
+    fn from(action: TorrentsAction) -> Self {
+        use TorrentsAction as TA;
+        match action {
+            TA::AddMagnet => Self::AddMagnet,
+            TA::SelectUp => Self::SelectUp,
+            TA::SelectDown => Self::SelectDown,
+            TA::Pause => Self::Pause,
+            TA::StartNow => Self::StartNow,
+            TA::StartAll => Self::StartAll,
+            TA::StopAll => Self::StopAll,
+            TA::DeleteWithoutFiles => Self::DeleteWithoutFiles,
+            TA::DeleteWithFiles => Self::DeleteWithFiles,
+            TA::UndoDelete => Self::UndoDelete,
+            TA::Move => Self::Move,
+            TA::SetLocation => Self::SetLocation,
+            TA::Verify => Self::Verify,
+            TA::Reannounce => Self::Reannounce,
+            TA::ShowFiles => Self::ShowFiles,
+            TA::ShowInfo => Self::ShowInfo,
+            TA::ToggleDetails => Self::ToggleDetails,
+            TA::ToggleRowHeight => Self::ToggleRowHeight,
+            TA::GoToRow => Self::GoToRow,
+            TA::ShowStats => Self::ShowStats,
+            TA::SpeedLimit => Self::SpeedLimit,
+            TA::GlobalSpeedLimit => Self::GlobalSpeedLimit,
+            TA::SeedLimits => Self::SeedLimits,
+            TA::SortBy => Self::SortBy,
+            TA::ToggleSortOrder => Self::ToggleSortOrder,
+            TA::CycleStatusFilter => Self::CycleStatusFilter,
+            TA::ToggleFinished => Self::ToggleFinished,
+            TA::ToggleAltSpeed => Self::ToggleAltSpeed,
+            TA::CopyMagnet => Self::CopyMagnet,
+            TA::OpenDownloadDir => Self::OpenDownloadDir,
+            TA::Export => Self::Export,
+            TA::CyclePriority => Self::CyclePriority,
+            TA::QueueUp => Self::QueueUp,
+            TA::QueueDown => Self::QueueDown,
+            TA::QueueTop => Self::QueueTop,
+            TA::QueueBottom => Self::QueueBottom,
+            TA::EditLabels => Self::EditLabels,
+            TA::EditTrackers => Self::EditTrackers,
+            TA::Rename => Self::Rename,
+        }
+    }
+}
+
+// Turns key presses into actions, keeping track of partially entered chords.
+pub struct KeyDispatcher {
+    general: HashMap<KeySequence, Action>,
+    tabs: HashMap<Tab, HashMap<KeySequence, Action>>,
+    pending: KeySequence,
+    last_key_at: Instant,
+    timeout: Duration,
+    count: Option<usize>,
+}
+
+// Plenty for any list, and keeps a held digit from overflowing
+const MAX_COUNT: usize = 9999;
+
+impl KeyDispatcher {
+    pub fn new(config: &Config) -> Self {
+        let keymap = config.keymap.read().unwrap();
+        let tabs = [Tab::Torrents, Tab::Search]
+            .into_iter()
+            .map(|tab| (tab, keymap.to_map_for_tab(tab)))
+            .collect();
+
+        // A binding for a tab that isn't shown does nothing
+        let general = keymap
+            .general_map()
+            .into_iter()
+            .filter_map(|(sequence, action)| match action {
+                Action::SwitchToTab(tab) => {
+                    let position = config.general.tabs.iter().position(|shown| *shown == tab)?;
+                    Some((
+                        sequence,
+                        Action::ChangeTab(u8::try_from(position + 1).ok()?),
+                    ))
+                }
+                action => Some((sequence, action)),
+            })
+            .collect();
+
+        Self {
+            general,
+            tabs,
+            pending: vec![],
+            last_key_at: Instant::now(),
+            timeout: Duration::from_millis(config.general.chord_timeout),
+            count: None,
+        }
+    }
+
+    // Digits bound on their own keep their action, unless a count has been
+    // started already.
+    fn push_count(&mut self, key: KeyEvent, tab: Tab) -> bool {
+        let KeyCode::Char(char) = key.code else {
+            return false;
+        };
+        let Some(digit) = char.to_digit(10) else {
+            return false;
+        };
+        if !self.pending.is_empty() || !key.modifiers.is_empty() {
+            return false;
+        }
+        if self.count.is_none() {
+            let sequence = vec![normalize_key(key.code, key.modifiers)];
+            if digit == 0
+                || self
+                    .keymaps(tab)
+                    .any(|keymap| keymap.contains_key(&sequence))
+            {
+                return false;
+            }
+        }
+
+        let count = self.count.unwrap_or(0) * 10 + digit as usize;
+        self.count = Some(count.min(MAX_COUNT));
+        true
+    }
+
+    // The count applies to movements only, and is dropped by any other key
+    fn with_count(&mut self, action: Option<Action>) -> Option<Action> {
+        match (self.count.take(), action) {
+            (Some(count), Some(action)) if count > 1 && action.takes_count() => {
+                Some(Action::Repeat(count, Box::new(action)))
+            }
+            (_, action) => action,
+        }
+    }
+
+    fn keymaps(&self, tab: Tab) -> impl Iterator<Item = &HashMap<KeySequence, Action>> {
+        self.tabs.get(&tab).into_iter().chain([&self.general])
+    }
+
+    fn key_to_action(&mut self, key: KeyEvent, tab: Tab) -> Option<Action> {
+        if self.last_key_at.elapsed() > self.timeout {
+            self.pending.clear();
+        }
+        self.last_key_at = Instant::now();
+
+        if self.push_count(key, tab) {
+            return None;
+        }
+
+        self.pending.push(normalize_key(key.code, key.modifiers));
+
+        // The tab's bindings win over general ones even when one is only the
+        // beginning of the other, so its keymap is gone through first as a whole
+        // (None inside means the keys so far start a longer binding)
+        let found = self.keymaps(tab).find_map(|keymap| {
+            if let Some(action) = keymap.get(&self.pending) {
+                return Some(Some(action.clone()));
+            }
+            let is_prefix = keymap.keys().any(|sequence| {
+                sequence.len() > self.pending.len() && sequence.starts_with(&self.pending)
+            });
+            is_prefix.then_some(None)
+        });
+        match found {
+            Some(Some(action)) => {
+                self.pending.clear();
+                return self.with_count(Some(action));
+            }
+            Some(None) => return None,
+            None => (),
+        }
+
+        // The chord went nowhere, so treat the key as the start of a new one
+        let was_chord = self.pending.len() > 1;
+        self.pending.clear();
+        if was_chord {
+            self.key_to_action(key, tab)
+        } else {
+            self.with_count(None)
+        }
+    }
+}
+
+pub fn event_to_action(
+    mode: Mode,
+    event: Event,
+    key_dispatcher: &mut KeyDispatcher,
+    current_tab: CurrentTab,
+) -> Option<Action> {
     use Action as A;
 
     // Handle CTRL+C first
@@ -71,48 +355,22 @@ pub fn event_to_action(mode: Mode, event: Event) -> Option<Action> {
         Event::Quit => Some(A::Quit),
         Event::Error => todo!(),
         Event::Render => Some(A::Render),
-        Event::Key(key) if mode == Mode::Input => Some(A::Input(key)),
-        Event::Key(key) => key_event_to_action(key),
-    }
-}
-
-fn key_event_to_action(key: KeyEvent) -> Option<Action> {
-    use Action as A;
-
-    match (key.modifiers, key.code) {
-        (KeyModifiers::CONTROL, KeyCode::Char('d')) => Some(A::ScrollDownPage),
-        (KeyModifiers::CONTROL, KeyCode::Char('u')) => Some(A::ScrollUpPage),
-        (_, keycode) => keycode_to_action(keycode),
-    }
-}
-
-fn keycode_to_action(key: KeyCode) -> Option<Action> {
-    use Action as A;
-    match key {
-        KeyCode::Char('q') | KeyCode::Char('Q') => Some(A::Quit),
-        KeyCode::Esc => Some(A::SoftQuit),
-        KeyCode::Tab => Some(A::ChangeFocus),
-        KeyCode::Home => Some(A::Home),
-        KeyCode::End => Some(A::End),
-        KeyCode::PageUp => Some(A::ScrollUpPage),
-        KeyCode::PageDown => Some(A::ScrollDownPage),
-        KeyCode::Char('j') | KeyCode::Down => Some(A::Down),
-        KeyCode::Char('k') | KeyCode::Up => Some(A::Up),
-        KeyCode::Char('h') | KeyCode::Left => Some(A::Left),
-        KeyCode::Char('l') | KeyCode::Right => Some(A::Right),
-        KeyCode::Char('?') | KeyCode::F(1) => Some(A::ShowHelp),
-        KeyCode::Char('s') => Some(A::ShowStats),
-        KeyCode::Char('f') => Some(A::ShowFiles),
-        KeyCode::Char('/') => Some(A::Search),
-        KeyCode::Char('a') => Some(A::AddMagnet),
-        KeyCode::Char('p') => Some(A::Pause),
-        KeyCode::Char('d') => Some(A::DeleteWithoutFiles),
-        KeyCode::Char('D') => Some(A::DeleteWithFiles),
-        KeyCode::Char(' ') => Some(A::Space),
-        KeyCode::Char(n @ '1'..='9') => {
-            Some(A::ChangeTab(n.to_digit(10).expect("This is ok") as u8))
+        Event::Key(key) if mode == Mode::Input => {
+            key_dispatcher.pending.clear();
+            key_dispatcher.count = None;
+            Some(A::Input(key))
+        }
+        Event::Key(key) => {
+            let tab = match current_tab {
+                CurrentTab::Torrents => Tab::Torrents,
+                CurrentTab::Search => Tab::Search,
+            };
+            key_dispatcher.key_to_action(key, tab)
         }
-        KeyCode::Enter => Some(A::Confirm),
-        _ => None,
+        // Clicks would interfere with typing into an input
+        Event::Mouse(_) if mode == Mode::Input => None,
+        Event::Mouse(mouse) => Some(A::Mouse(mouse)),
+        Event::Paste(text) if mode == Mode::Input => Some(A::Paste(text)),
+        Event::Paste(_) => None,
     }
 }
@@ -1,25 +1,37 @@
-use rm_config::Config;
-use std::sync::Arc;
+use rm_config::{keymap::KeymapConfig, Config, Server};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use crate::{
-    action::{event_to_action, Action, Mode},
+    action::{event_to_action, Action, KeyDispatcher, Mode},
     transmission::{self, TorrentAction},
     tui::Tui,
-    ui::{components::Component, MainWindow},
+    ui::{components::Component, global_popups::ErrorPopup, MainWindow},
 };
 
 use anyhow::{Error, Result};
 use tokio::sync::{
     mpsc::{self, UnboundedReceiver, UnboundedSender},
-    Mutex,
+    Mutex, Notify,
+};
+use transmission_rpc::{
+    types::{BasicAuth, SessionGet},
+    TransClient,
 };
-use transmission_rpc::{types::SessionGet, TransClient};
 
 #[derive(Clone)]
 pub struct Ctx {
     pub client: Arc<Mutex<TransClient>>,
     pub config: Arc<Config>,
-    pub session_info: Arc<SessionGet>,
+    // Replaced when switching servers
+    session_info: Arc<std::sync::RwLock<Arc<SessionGet>>>,
+    pub activity: Arc<Activity>,
+    pub connection: Arc<Connection>,
     action_tx: UnboundedSender<Action>,
     trans_tx: UnboundedSender<TorrentAction>,
 }
@@ -27,6 +39,7 @@ pub struct Ctx {
 impl Ctx {
     async fn new(
         client: Arc<Mutex<TransClient>>,
+        auth: BasicAuth,
         config: Config,
         action_tx: UnboundedSender<Action>,
         trans_tx: UnboundedSender<TorrentAction>,
@@ -34,24 +47,34 @@ impl Ctx {
         let response = client.lock().await.session_get().await;
         match response {
             Ok(res) => {
-                let session_info = Arc::new(res.arguments);
-                return Ok(Self {
+                let session_info = Arc::new(std::sync::RwLock::new(Arc::new(res.arguments)));
+                let activity = Arc::new(Activity::new(config.idle_timeout()));
+                let servers = config.servers();
+                let connection = Connection::new(servers[0].clone(), auth, servers.len() > 1);
+                Ok(Self {
                     client,
+                    activity,
+                    connection: Arc::new(connection),
                     config: Arc::new(config),
                     action_tx,
                     trans_tx,
                     session_info,
-                });
+                })
             }
             Err(e) => {
                 let config_path = Config::get_config_path().to_str().unwrap();
-                return Err(Error::msg(format!(
-                    "{e}\nIs the connection info in {config_path} correct?"
-                )));
+                Err(Error::msg(format!(
+                    "{}\nIs the connection info in {config_path} correct?",
+                    transmission::utils::describe_error(&*e)
+                )))
             }
         }
     }
 
+    pub fn session_info(&self) -> Arc<SessionGet> {
+        Arc::clone(&self.session_info.read().unwrap())
+    }
+
     pub(crate) fn send_action(&self, action: Action) {
         self.action_tx.send(action).unwrap();
     }
@@ -61,22 +84,172 @@ impl Ctx {
     }
 }
 
+// How long to wait for a server being switched to
+const SWITCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Holding the refresh key shouldn't flood the daemon
+const REFRESH_DEBOUNCE: Duration = Duration::from_secs(1);
+
+// Lets the fetchers back off while nobody is using the TUI, and fetch right
+// away when asked to.
+pub struct Activity {
+    last_event_at: std::sync::Mutex<Instant>,
+    last_refresh_at: std::sync::Mutex<Option<Instant>>,
+    idle_timeout: Option<Duration>,
+    notify: Notify,
+    refresh: Notify,
+}
+
+impl Activity {
+    fn new(idle_timeout: Option<Duration>) -> Self {
+        Self {
+            last_event_at: std::sync::Mutex::new(Instant::now()),
+            last_refresh_at: std::sync::Mutex::new(None),
+            idle_timeout,
+            notify: Notify::new(),
+            refresh: Notify::new(),
+        }
+    }
+
+    pub fn touch(&self) {
+        *self.last_event_at.lock().unwrap() = Instant::now();
+        self.notify.notify_waiters();
+    }
+
+    pub fn refresh(&self) {
+        {
+            let mut last_refresh_at = self.last_refresh_at.lock().unwrap();
+            if last_refresh_at.is_some_and(|at| at.elapsed() < REFRESH_DEBOUNCE) {
+                return;
+            }
+            *last_refresh_at = Some(Instant::now());
+        }
+        self.touch();
+        self.refresh.notify_waiters();
+    }
+
+    fn is_idle(&self) -> bool {
+        self.idle_timeout
+            .is_some_and(|timeout| self.last_event_at.lock().unwrap().elapsed() > timeout)
+    }
+
+    // Sleeps for the given interval, and then for as long as the TUI is idle.
+    // A refresh cuts the wait short.
+    pub async fn wait(&self, interval: Duration) {
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => (),
+            _ = self.refresh.notified() => return,
+        }
+        loop {
+            // Created before the check so that a touch in between isn't missed
+            let notified = self.notify.notified();
+            if !self.is_idle() {
+                break;
+            }
+            notified.await;
+        }
+    }
+}
+
+// Whether the daemon is reachable. Shared by the fetchers so that losing and
+// regaining the connection is reported once, not by each failing fetch.
+pub struct Connection {
+    is_lost: AtomicBool,
+    // When the torrents were last fetched
+    last_update_at: std::sync::Mutex<Option<Instant>>,
+    server: std::sync::Mutex<Server>,
+    // What the client of the server authenticates with
+    auth: std::sync::Mutex<BasicAuth>,
+    // The name is only worth showing if there are other servers
+    show_server_name: bool,
+}
+
+impl Connection {
+    fn new(server: Server, auth: BasicAuth, show_server_name: bool) -> Self {
+        Self {
+            is_lost: AtomicBool::new(false),
+            last_update_at: std::sync::Mutex::new(None),
+            server: std::sync::Mutex::new(server),
+            auth: std::sync::Mutex::new(auth),
+            show_server_name,
+        }
+    }
+
+    pub fn server(&self) -> Server {
+        self.server.lock().unwrap().clone()
+    }
+
+    pub fn server_name(&self) -> Option<String> {
+        self.show_server_name
+            .then(|| self.server.lock().unwrap().name.clone())
+    }
+
+    pub fn auth(&self) -> BasicAuth {
+        self.auth.lock().unwrap().clone()
+    }
+
+    pub fn set_auth(&self, auth: BasicAuth) {
+        *self.auth.lock().unwrap() = auth;
+    }
+
+    fn switch_to(&self, server: Server, auth: BasicAuth) {
+        self.is_lost.store(false, Ordering::Relaxed);
+        *self.last_update_at.lock().unwrap() = None;
+        *self.server.lock().unwrap() = server;
+        self.set_auth(auth);
+    }
+
+    pub fn updated(&self) {
+        *self.last_update_at.lock().unwrap() = Some(Instant::now());
+    }
+
+    pub fn since_update(&self) -> Option<Duration> {
+        self.last_update_at.lock().unwrap().map(|at| at.elapsed())
+    }
+
+    pub fn is_lost(&self) -> bool {
+        self.is_lost.load(Ordering::Relaxed)
+    }
+
+    // True if the connection was fine until now
+    pub fn lose(&self) -> bool {
+        !self.is_lost.swap(true, Ordering::Relaxed)
+    }
+
+    // True if the connection was lost until now
+    pub fn regain(&self) -> bool {
+        self.is_lost.swap(false, Ordering::Relaxed)
+    }
+}
+
 pub struct App {
     should_quit: bool,
     ctx: Ctx,
     action_rx: UnboundedReceiver<Action>,
     main_window: MainWindow,
     mode: Mode,
+    key_dispatcher: KeyDispatcher,
+    // Index into Config::servers(), set once a switch went through
+    server_index: Arc<AtomicUsize>,
+    // A switch is being tried, see switch_server
+    switching: Arc<AtomicBool>,
 }
 
 impl App {
     pub async fn new(config: Config) -> Result<Self> {
         let (action_tx, action_rx) = mpsc::unbounded_channel();
 
-        let client = Arc::new(Mutex::new(transmission::utils::client_from_config(&config)));
+        let server = &config.servers()[0];
+        let auth = transmission::utils::credentials(server).await?;
+        let client = Arc::new(Mutex::new(transmission::utils::client_with_auth(
+            server,
+            auth.clone(),
+        )?));
+
+        let key_dispatcher = KeyDispatcher::new(&config);
 
         let (trans_tx, trans_rx) = mpsc::unbounded_channel();
-        let ctx = Ctx::new(client, config, action_tx, trans_tx).await?;
+        let ctx = Ctx::new(client, auth, config, action_tx, trans_tx).await?;
 
         tokio::spawn(transmission::action_handler(ctx.clone(), trans_rx));
         Ok(Self {
@@ -85,11 +258,14 @@ impl App {
             action_rx,
             ctx,
             mode: Mode::Normal,
+            key_dispatcher,
+            server_index: Arc::new(AtomicUsize::new(0)),
+            switching: Arc::new(AtomicBool::new(false)),
         })
     }
 
     pub async fn run(&mut self) -> Result<()> {
-        let mut tui = Tui::new()?;
+        let mut tui = Tui::new(self.ctx.config.general.mouse)?;
 
         tui.enter()?;
 
@@ -114,7 +290,13 @@ impl App {
                 },
 
                 event = tui_event => {
-                    if let Some(action) = event_to_action(self.mode, event.unwrap()) {
+                    self.ctx.activity.touch();
+                    if let Some(action) = event_to_action(
+                        self.mode,
+                        event.unwrap(),
+                        &mut self.key_dispatcher,
+                        self.main_window.current_tab(),
+                    ) {
                         if let Some(action) = self.update(action).await {
                             self.ctx.action_tx.send(action).unwrap();
                         }
@@ -147,6 +329,84 @@ impl App {
         Ok(())
     }
 
+    fn reload_keymap(&mut self) -> Option<Action> {
+        match KeymapConfig::init() {
+            Ok(keymap) => {
+                *self.ctx.config.keymap.write().unwrap() = keymap;
+                self.key_dispatcher = KeyDispatcher::new(&self.ctx.config);
+                Some(Action::Render)
+            }
+            Err(e) => {
+                let error_popup = ErrorPopup::new("Failed to reload the keymap", e.to_string());
+                Some(Action::Error(Box::new(error_popup)))
+            }
+        }
+    }
+
+    // The current server is kept if the next one can't be reached. It's
+    // tried in the background, so a server that doesn't answer doesn't hold
+    // up the UI, and further switches are ignored until that's over.
+    fn switch_server(&mut self) -> Option<Action> {
+        let servers = self.ctx.config.servers();
+        if servers.len() < 2 {
+            return Some(Action::ShowStatus(
+                "No other servers in the config".to_string(),
+            ));
+        }
+        if self.switching.swap(true, Ordering::Relaxed) {
+            return None;
+        }
+
+        let index = (self.server_index.load(Ordering::Relaxed) + 1) % servers.len();
+        let server = servers[index].clone();
+        let ctx = self.ctx.clone();
+        let server_index = Arc::clone(&self.server_index);
+        let switching = Arc::clone(&self.switching);
+        tokio::spawn(async move {
+            ctx.send_action(Action::TaskStarted(format!(
+                "Connecting to {}…",
+                server.name
+            )));
+            let auth = transmission::utils::credentials(&server).await;
+            let client = auth.and_then(|auth| {
+                transmission::utils::client_with_auth(&server, auth.clone())
+                    .map(|client| (client, auth))
+            });
+            let error = match client {
+                Ok((mut client, auth)) => {
+                    let response = tokio::time::timeout(SWITCH_TIMEOUT, client.session_get()).await;
+                    match response {
+                        Ok(Ok(response)) => {
+                            *ctx.client.lock().await = client;
+                            *ctx.session_info.write().unwrap() = Arc::new(response.arguments);
+                            ctx.send_action(Action::TaskDone(format!(
+                                "Switched to {}",
+                                server.name
+                            )));
+                            ctx.connection.switch_to(server, auth);
+                            server_index.store(index, Ordering::Relaxed);
+                            switching.store(false, Ordering::Relaxed);
+                            ctx.send_action(Action::ServerSwitched);
+                            return;
+                        }
+                        Ok(Err(e)) => transmission::utils::describe_error(&*e),
+                        Err(_) => "Timed out".to_string(),
+                    }
+                }
+                Err(e) => e.to_string(),
+            };
+
+            switching.store(false, Ordering::Relaxed);
+            ctx.send_action(Action::TaskFinished(Ok(())));
+            let error_popup = ErrorPopup::new(
+                "Failed to switch servers",
+                format!("{}: {error}", server.name),
+            );
+            ctx.send_action(Action::Error(Box::new(error_popup)));
+        });
+        None
+    }
+
     #[must_use]
     async fn update(&mut self, action: Action) -> Option<Action> {
         use Action as A;
@@ -168,6 +428,27 @@ impl App {
                 Some(A::Render)
             }
 
+            A::ReloadConfig => self.reload_keymap(),
+
+            A::Refresh => {
+                self.ctx.activity.refresh();
+                None
+            }
+
+            A::SwitchServer => self.switch_server(),
+
+            // As if the key was pressed that many times
+            A::Repeat(count, action) => {
+                let mut result = None;
+                for _ in 0..*count {
+                    result = self
+                        .main_window
+                        .handle_actions((**action).clone())
+                        .or(result);
+                }
+                result
+            }
+
             _ => self.main_window.handle_actions(action),
         }
     }
@@ -1,10 +1,9 @@
-use std::{fs::File, io::Read};
+use std::path::PathBuf;
 
-use anyhow::Result;
-use base64::Engine;
+use anyhow::{Error, Result};
 use clap::{Parser, Subcommand};
 use rm_config::Config;
-use transmission_rpc::types::TorrentAddArgs;
+use transmission_rpc::types::TorrentAddedOrDuplicate;
 
 use crate::transmission;
 
@@ -13,50 +12,65 @@ use crate::transmission;
 pub struct Args {
     #[command(subcommand)]
     pub command: Option<Commands>,
+    /// Directory with config.toml and keymap.toml, instead of
+    /// $XDG_CONFIG_HOME/rustmission
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config_dir: Option<PathBuf>,
+    /// Print the default config.toml, with its comments, and exit
+    #[arg(long, conflicts_with = "print_default_keymap")]
+    pub print_default_config: bool,
+    /// Print the default keymap.toml, with every action bound, and exit
+    #[arg(long)]
+    pub print_default_keymap: bool,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-    AddTorrent { torrent: String },
+    /// Add a magnet link, URL or .torrent file without starting the TUI
+    #[command(visible_alias = "add")]
+    AddTorrent {
+        torrent: String,
+        /// Add it stopped
+        #[arg(long)]
+        paused: bool,
+    },
 }
 
 pub async fn handle_command(config: &Config, command: Commands) -> Result<()> {
     match command {
-        Commands::AddTorrent { torrent } => add_torrent(config, torrent).await?,
+        Commands::AddTorrent { torrent, paused } => add_torrent(config, torrent, paused).await?,
     }
     Ok(())
 }
 
-async fn add_torrent(config: &Config, torrent: String) -> Result<()> {
-    let mut transclient = transmission::utils::client_from_config(&config);
-    let args = {
-        if torrent.starts_with("magnet:")
-            || torrent.starts_with("http:")
-            || torrent.starts_with("https:")
-        {
-            TorrentAddArgs {
-                filename: Some(torrent),
-                ..Default::default()
-            }
-        } else {
-            let mut torrent_file = File::open(torrent)?;
-            let mut buf = vec![];
-            torrent_file.read_to_end(&mut buf).unwrap();
-            let metainfo = base64::engine::general_purpose::STANDARD.encode(buf);
-            TorrentAddArgs {
-                metainfo: Some(metainfo),
-                ..Default::default()
+// Same as adding from the TUI, e.g. for a browser's magnet handler
+async fn add_torrent(config: &Config, torrent: String, paused: bool) -> Result<()> {
+    let mut transclient = transmission::utils::client_from_config(config).await?;
+    let args = transmission::torrent_add_args(&torrent, None, paused).map_err(Error::msg)?;
+
+    let response = match transclient.torrent_add(args).await {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!("error while adding a torrent: {e}");
+            if e.to_string().contains("expected value at line") {
+                eprintln!("Check whether your arguments are valid.");
             }
+
+            std::process::exit(1);
         }
     };
 
-    if let Err(e) = transclient.torrent_add(args).await {
-        eprintln!("error while adding a torrent: {e}");
-        if e.to_string().contains("expected value at line") {
-            eprintln!("Check whether your arguments are valid.");
+    // Magnets may not have a name until their metadata is fetched
+    let name = |added: transmission_rpc::types::Torrent| added.name.unwrap_or(torrent);
+    match response.arguments {
+        TorrentAddedOrDuplicate::TorrentAdded(added) => println!("Added {}", name(added)),
+        TorrentAddedOrDuplicate::TorrentDuplicate(duplicate) => {
+            println!("Already added: {}", name(duplicate));
         }
-
-        std::process::exit(1);
-    };
+        TorrentAddedOrDuplicate::Error => {
+            eprintln!("error while adding a torrent: {}", response.result);
+            std::process::exit(1);
+        }
+    }
     Ok(())
 }
@@ -16,6 +16,19 @@ use clap::Parser;
 async fn main() -> Result<()> {
     let args = cli::Args::parse();
 
+    // Printed as shipped rather than serialized, so the comments are kept
+    if args.print_default_config {
+        print!("{}", rm_config::DEFAULT_CONFIG);
+        return Ok(());
+    }
+    if args.print_default_keymap {
+        print!("{}", rm_config::keymap::DEFAULT_KEYMAP);
+        return Ok(());
+    }
+
+    if let Some(config_dir) = args.config_dir {
+        Config::set_config_dir(config_dir)?;
+    }
     let config = Config::init()?;
 
     if let Some(command) = args.command {
@@ -1,45 +1,107 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
 
+use arboard::Clipboard;
+use base64::Engine;
 use tokio::sync::{mpsc::UnboundedReceiver, oneshot};
 use transmission_rpc::types::{
-    Id, SessionGet, Torrent, TorrentAction as RPCAction, TorrentAddArgs, TorrentSetArgs,
+    Id, Result as RpcResult, RpcResponse, RpcResponseArgument, SessionGet, SessionSetArgs, Torrent,
+    TorrentAction as RPCAction, TorrentAddArgs, TorrentGetField, TorrentSetArgs, TorrentStatus,
 };
 
+use super::raw_rpc::{RawClient, SessionLimits};
 use crate::{action::Action, app, ui::global_popups::ErrorPopup};
 
 #[derive(Debug)]
 pub enum TorrentAction {
-    // Magnet/URL, Directory
-    Add(String, Option<String>),
+    // Magnet/URL/Path, Directory, Paused
+    Add(String, Option<String>, bool),
+    // Same as Add, but reports how many of them succeeded
+    AddMany(Vec<String>, Option<String>, bool),
     Stop(Vec<Id>),
     Start(Vec<Id>),
+    // Ignoring the queue
+    StartNow(Vec<Id>),
     DeleteWithoutFiles(Vec<Id>),
     DeleteWithFiles(Vec<Id>),
+    Verify(Vec<Id>),
+    Reannounce(Vec<Id>),
+    // Ids, New location, Move existing data
+    Move(Vec<Id>, PathBuf, bool),
     GetTorrentInfo(Id, Arc<Mutex<Option<Torrent>>>),
     GetSessionGet(oneshot::Sender<SessionGet>),
+    GetSessionLimits(Arc<Mutex<Option<SessionLimits>>>),
     SetArgs(Box<TorrentSetArgs>, Option<Vec<Id>>),
+    // Id, Path in the torrent, New name of its last part
+    RenamePath(Id, String, String),
+    // Adds or removes trackers, then fetches the torrent again into the Option
+    SetTrackers(Box<TorrentSetArgs>, Id, Arc<Mutex<Option<Torrent>>>),
+    SetSessionArgs(Box<SessionSetArgs>),
+    // Enabled, State shown in the UI (reverted if the request fails)
+    SetAltSpeed(bool, Arc<Mutex<bool>>),
+    CopyMagnet(Id),
+    // Adds the torrents of the latest delete again
+    UndoDelete,
+    // Deletes without files, for [auto_remove]. Id, Name, Sent to once it's
+    // gone. Unlike the user's own deletes it can't be undone.
+    AutoRemove(Id, String, oneshot::Sender<()>),
+}
+
+// How many deletes can be undone
+const TRASH_SIZE: usize = 10;
+
+// Enough to add a deleted torrent again. The metainfo can't be had over RPC,
+// so it's added from its magnet link.
+struct DeletedTorrent {
+    magnet: String,
+    download_dir: Option<String>,
+    paused: bool,
+}
+
+struct DeletedBatch {
+    torrents: Vec<DeletedTorrent>,
+    with_files: bool,
 }
 
 // TODO: make all the options use the same type of interface. Probably use a sender everywhere
 pub async fn action_handler(ctx: app::Ctx, mut trans_rx: UnboundedReceiver<TorrentAction>) {
+    // On some platforms whatever was copied lives only as long as the clipboard does
+    let mut clipboard = None;
+    // Oldest first, so that undo brings back the latest delete
+    let mut trash = VecDeque::new();
+
     while let Some(action) = trans_rx.recv().await {
         match action {
-            TorrentAction::Add(ref url, directory) => {
-                let args = TorrentAddArgs {
-                    filename: Some(url.clone()),
-                    download_dir: directory,
-                    ..Default::default()
-                };
-
-                if let Err(e) = ctx.client.lock().await.torrent_add(args).await {
-                    let error_title = "Failed to add a torrent";
-                    let msg = "Failed to add torrent with URL/Path:\n\"".to_owned()
-                        + url
-                        + "\"\n"
-                        + &e.to_string();
-                    let error_popup = Box::new(ErrorPopup::new(error_title, msg));
-                    ctx.send_action(Action::Error(error_popup));
+            TorrentAction::Add(url, directory, paused) => {
+                ctx.send_action(Action::TaskStarted("Adding torrent…".to_string()));
+                if let Some(torrents) = read_torrent_list(&url) {
+                    let result = add_torrents(&ctx, torrents, directory, paused).await;
+                    ctx.send_action(Action::TaskFinished(result));
+                    continue;
                 }
+
+                let result = match add_torrent(&ctx, &url, directory, paused).await {
+                    // Show it (and whether it's stopped) right away
+                    Ok(()) => {
+                        ctx.activity.refresh();
+                        Ok(())
+                    }
+                    Err(msg) => {
+                        let error_popup = Box::new(ErrorPopup::new("Failed to add a torrent", msg));
+                        ctx.send_action(Action::Error(error_popup));
+                        Err("Failed to add the torrent".to_string())
+                    }
+                };
+                ctx.send_action(Action::TaskFinished(result));
+            }
+            TorrentAction::AddMany(torrents, directory, paused) => {
+                let text = format!("Adding {} torrents…", torrents.len());
+                ctx.send_action(Action::TaskStarted(text));
+                let result = add_torrents(&ctx, torrents, directory, paused).await;
+                ctx.send_action(Action::TaskFinished(result));
             }
             TorrentAction::Stop(ids) => {
                 ctx.client
@@ -57,35 +119,196 @@ pub async fn action_handler(ctx: app::Ctx, mut trans_rx: UnboundedReceiver<Torre
                     .await
                     .unwrap();
             }
-            TorrentAction::DeleteWithFiles(ids) => {
-                ctx.client
+            TorrentAction::StartNow(ids) => {
+                ctx.send_action(Action::TaskStarted("Starting…".to_string()));
+                let result = ctx
+                    .client
                     .lock()
                     .await
-                    .torrent_remove(ids, true)
+                    .torrent_action(RPCAction::StartNow, ids)
+                    .await;
+                let result = rpc_result(result).map_err(|e| format!("Failed to start: {e}"));
+                ctx.send_action(Action::TaskFinished(result));
+            }
+            TorrentAction::Verify(ids) => {
+                ctx.send_action(Action::TaskStarted("Verifying…".to_string()));
+                let result = ctx
+                    .client
+                    .lock()
                     .await
-                    .unwrap();
+                    .torrent_action(RPCAction::Verify, ids)
+                    .await;
+                let result = rpc_result(result).map_err(|e| format!("Failed to verify: {e}"));
+                ctx.send_action(Action::TaskFinished(result));
+            }
+            TorrentAction::Reannounce(ids) => {
+                ctx.send_action(Action::TaskStarted("Reannouncing…".to_string()));
+                let done = match ids.len() {
+                    1 => "Reannounced 1 torrent".to_string(),
+                    n => format!("Reannounced {n} torrents"),
+                };
+                let result = ctx
+                    .client
+                    .lock()
+                    .await
+                    .torrent_action(RPCAction::Reannounce, ids)
+                    .await;
+                match rpc_result(result) {
+                    Ok(()) => ctx.send_action(Action::TaskDone(done)),
+                    Err(e) => ctx.send_action(Action::TaskFinished(Err(format!(
+                        "Failed to reannounce: {e}"
+                    )))),
+                }
+            }
+            TorrentAction::DeleteWithFiles(ids) => {
+                ctx.send_action(Action::TaskStarted("Deleting with files…".to_string()));
+                let result = delete_torrents(&ctx, &mut trash, ids, true).await;
+                ctx.send_action(Action::TaskFinished(result));
             }
             TorrentAction::DeleteWithoutFiles(ids) => {
-                ctx.client
+                ctx.send_action(Action::TaskStarted("Deleting…".to_string()));
+                let result = delete_torrents(&ctx, &mut trash, ids, false).await;
+                ctx.send_action(Action::TaskFinished(result));
+            }
+            TorrentAction::AutoRemove(id, name, removed_tx) => {
+                ctx.send_action(Action::TaskStarted(format!("Auto-removing \"{name}\"…")));
+                let result = ctx
+                    .client
                     .lock()
                     .await
-                    .torrent_remove(ids, false)
+                    .torrent_remove(vec![id], false)
+                    .await;
+                match rpc_result(result) {
+                    Ok(()) => {
+                        let _ = removed_tx.send(());
+                        ctx.send_action(Action::TaskDone(format!("Auto-removed \"{name}\"")));
+                    }
+                    Err(e) => ctx.send_action(Action::TaskFinished(Err(format!(
+                        "Failed to auto-remove \"{name}\": {e}"
+                    )))),
+                }
+            }
+            TorrentAction::UndoDelete => {
+                let Some(batch) = trash.pop_back() else {
+                    ctx.send_action(Action::ShowStatus("Nothing to undo".to_string()));
+                    continue;
+                };
+                ctx.send_action(Action::TaskStarted("Restoring…".to_string()));
+                let result = restore_torrents(&ctx, batch).await;
+                ctx.send_action(Action::TaskFinished(result));
+            }
+            TorrentAction::Move(ids, location, move_from) => {
+                let (task, failure, error_title) = if move_from {
+                    ("Moving…", "move", "Failed to move a torrent")
+                } else {
+                    (
+                        "Setting location…",
+                        "set the location of",
+                        "Failed to set a torrent's location",
+                    )
+                };
+                ctx.send_action(Action::TaskStarted(task.to_string()));
+                let location = location.to_string_lossy().into_owned();
+                let result = ctx
+                    .client
+                    .lock()
                     .await
-                    .unwrap();
+                    .torrent_set_location(ids, location.clone(), Some(move_from))
+                    .await;
+
+                if let Err(error) = rpc_result(result) {
+                    let msg = format!("Failed to {failure} torrent to:\n\"{location}\"\n{error}");
+                    let error_popup = Box::new(ErrorPopup::new(error_title, msg));
+                    ctx.send_action(Action::Error(error_popup));
+                    ctx.send_action(Action::TaskFinished(Err(format!(
+                        "Failed to {failure} the torrent"
+                    ))));
+                } else {
+                    // So that the new directory shows up without waiting
+                    ctx.activity.refresh();
+                    ctx.send_action(Action::TaskFinished(Ok(())));
+                }
+            }
+            // Fetched over and over for the details pane, so a torrent removed
+            // in the meantime or a failed request only leaves the old info
+            TorrentAction::GetSessionLimits(session_limits) => {
+                let client = RawClient::new(&ctx.connection.server(), ctx.connection.auth());
+                let response = match client {
+                    Ok(mut client) => client.session_limits().await,
+                    Err(e) => Err(e),
+                };
+                match response {
+                    Ok(new_session_limits) => {
+                        *session_limits.lock().unwrap() = Some(new_session_limits);
+                        ctx.send_action(Action::Render);
+                    }
+                    Err(e) => {
+                        let error_popup =
+                            ErrorPopup::new("Failed to get the speed limits", e.to_string());
+                        ctx.send_action(Action::Error(Box::new(error_popup)));
+                    }
+                }
             }
             TorrentAction::GetTorrentInfo(id, torrent_info) => {
-                let new_torrent_info = ctx
+                let response = ctx
                     .client
                     .lock()
                     .await
                     .torrent_get(None, Some(vec![id]))
+                    .await;
+                if let Some(new_torrent_info) = response
+                    .ok()
+                    .and_then(|response| response.arguments.torrents.into_iter().next())
+                {
+                    *torrent_info.lock().unwrap() = Some(new_torrent_info);
+                    ctx.send_action(Action::Render);
+                }
+            }
+            TorrentAction::RenamePath(id, path, name) => {
+                ctx.send_action(Action::TaskStarted("Renaming…".to_string()));
+                let result = ctx
+                    .client
+                    .lock()
                     .await
-                    .unwrap()
-                    .arguments
-                    .torrents
-                    .pop()
-                    .unwrap();
-                *torrent_info.lock().unwrap() = Some(new_torrent_info);
+                    .torrent_rename_path(vec![id], path.clone(), name)
+                    .await;
+
+                if let Err(error) = rpc_result(result) {
+                    let msg = format!("Failed to rename \"{path}\"\n{error}");
+                    let error_popup = Box::new(ErrorPopup::new("Failed to rename", msg));
+                    ctx.send_action(Action::Error(error_popup));
+                    ctx.send_action(Action::TaskFinished(Err("Failed to rename".to_string())));
+                } else {
+                    // So that the new name shows up without waiting
+                    ctx.activity.refresh();
+                    ctx.send_action(Action::TaskFinished(Ok(())));
+                }
+            }
+            TorrentAction::SetTrackers(args, id, torrent) => {
+                ctx.send_action(Action::TaskStarted("Updating trackers…".to_string()));
+                let result = ctx
+                    .client
+                    .lock()
+                    .await
+                    .torrent_set(*args, Some(vec![id.clone()]))
+                    .await;
+                let result =
+                    rpc_result(result).map_err(|e| format!("Failed to update trackers: {e}"));
+                ctx.send_action(Action::TaskFinished(result));
+
+                let fields = vec![TorrentGetField::Trackers];
+                let updated_torrent = ctx
+                    .client
+                    .lock()
+                    .await
+                    .torrent_get(Some(fields), Some(vec![id]))
+                    .await
+                    .ok()
+                    .and_then(|response| response.arguments.torrents.into_iter().next());
+                if let Some(updated_torrent) = updated_torrent {
+                    *torrent.lock().unwrap() = Some(updated_torrent);
+                    ctx.send_action(Action::Render);
+                }
             }
             TorrentAction::SetArgs(args, ids) => {
                 ctx.client
@@ -95,6 +318,66 @@ pub async fn action_handler(ctx: app::Ctx, mut trans_rx: UnboundedReceiver<Torre
                     .await
                     .unwrap();
             }
+            TorrentAction::SetSessionArgs(args) => {
+                let result = ctx.client.lock().await.session_set(*args).await;
+
+                let error = match result {
+                    Ok(response) if response.is_ok() => None,
+                    Ok(response) => Some(response.result),
+                    Err(e) => Some(e.to_string()),
+                };
+                if let Some(error) = error {
+                    let error_popup =
+                        Box::new(ErrorPopup::new("Failed to change session settings", error));
+                    ctx.send_action(Action::Error(error_popup));
+                }
+            }
+            TorrentAction::SetAltSpeed(enabled, alt_speed_enabled) => {
+                let args = SessionSetArgs {
+                    alt_speed_enabled: Some(enabled),
+                    ..Default::default()
+                };
+                let result = ctx.client.lock().await.session_set(args).await;
+
+                let error = match result {
+                    Ok(response) if response.is_ok() => None,
+                    Ok(response) => Some(response.result),
+                    Err(e) => Some(e.to_string()),
+                };
+                if let Some(error) = error {
+                    *alt_speed_enabled.lock().unwrap() = !enabled;
+                    let error_popup = Box::new(ErrorPopup::new(
+                        "Failed to toggle alternative speed limits",
+                        error,
+                    ));
+                    ctx.send_action(Action::Error(error_popup));
+                }
+            }
+            TorrentAction::CopyMagnet(id) => {
+                let fields = vec![TorrentGetField::HashString, TorrentGetField::Name];
+                let torrent = ctx
+                    .client
+                    .lock()
+                    .await
+                    .torrent_get(Some(fields), Some(vec![id]))
+                    .await
+                    .ok()
+                    .and_then(|response| response.arguments.torrents.into_iter().next());
+
+                let result = match torrent {
+                    Some(torrent) => copy_to_clipboard(&mut clipboard, magnet_link(&torrent))
+                        .map_err(|e| e.to_string()),
+                    None => Err("The torrent couldn't be fetched".to_string()),
+                };
+                match result {
+                    Ok(()) => ctx.send_action(Action::ShowStatus("Copied the magnet link".into())),
+                    Err(msg) => {
+                        let error_popup =
+                            Box::new(ErrorPopup::new("Failed to copy the magnet link", msg));
+                        ctx.send_action(Action::Error(error_popup));
+                    }
+                }
+            }
             TorrentAction::GetSessionGet(sender) => {
                 let session_get = ctx
                     .client
@@ -109,3 +392,233 @@ pub async fn action_handler(ctx: app::Ctx, mut trans_rx: UnboundedReceiver<Torre
         }
     }
 }
+
+async fn add_torrent(
+    ctx: &app::Ctx,
+    torrent: &str,
+    directory: Option<String>,
+    paused: bool,
+) -> Result<(), String> {
+    let args = torrent_add_args(torrent, directory, paused)?;
+    let result = ctx.client.lock().await.torrent_add(args).await;
+    rpc_result(result)
+        .map_err(|e| format!("Failed to add torrent with URL/Path:\n\"{torrent}\"\n{e}"))
+}
+
+// The torrents are looked up first, as there's nothing to undo with afterwards.
+// If that fails they're still deleted, just not remembered.
+async fn delete_torrents(
+    ctx: &app::Ctx,
+    trash: &mut VecDeque<DeletedBatch>,
+    ids: Vec<Id>,
+    with_files: bool,
+) -> Result<(), String> {
+    let fields = vec![
+        TorrentGetField::HashString,
+        TorrentGetField::Name,
+        TorrentGetField::MagnetLink,
+        TorrentGetField::DownloadDir,
+        TorrentGetField::Status,
+    ];
+    let torrents = ctx
+        .client
+        .lock()
+        .await
+        .torrent_get(Some(fields), Some(ids.clone()))
+        .await
+        .map(|response| response.arguments.torrents)
+        .unwrap_or_default();
+
+    let result = ctx
+        .client
+        .lock()
+        .await
+        .torrent_remove(ids, with_files)
+        .await;
+    rpc_result(result).map_err(|e| format!("Failed to delete: {e}"))?;
+
+    let torrents: Vec<_> = torrents
+        .into_iter()
+        .map(|torrent| DeletedTorrent {
+            magnet: torrent
+                .magnet_link
+                .clone()
+                .unwrap_or_else(|| magnet_link(&torrent)),
+            download_dir: torrent.download_dir,
+            paused: torrent.status == Some(TorrentStatus::Stopped),
+        })
+        .collect();
+    if !torrents.is_empty() {
+        if trash.len() == TRASH_SIZE {
+            trash.pop_front();
+        }
+        trash.push_back(DeletedBatch {
+            torrents,
+            with_files,
+        });
+    }
+    Ok(())
+}
+
+// Torrents deleted along with their files start downloading from scratch
+async fn restore_torrents(ctx: &app::Ctx, batch: DeletedBatch) -> Result<(), String> {
+    let count = batch.torrents.len();
+    let mut failures = vec![];
+    for torrent in batch.torrents {
+        if let Err(msg) =
+            add_torrent(ctx, &torrent.magnet, torrent.download_dir, torrent.paused).await
+        {
+            failures.push(msg);
+        }
+    }
+
+    ctx.activity.refresh();
+    if !failures.is_empty() {
+        let error_popup = Box::new(ErrorPopup::new(
+            "Failed to restore some torrents",
+            failures.join("\n\n"),
+        ));
+        ctx.send_action(Action::Error(error_popup));
+        return Err(format!(
+            "Restored {}, {} failed",
+            count - failures.len(),
+            failures.len()
+        ));
+    }
+
+    let text = if batch.with_files {
+        format!("Restored {count}, their files were deleted so they download again")
+    } else {
+        format!("Restored {count}")
+    };
+    ctx.send_action(Action::ShowStatus(text));
+    Ok(())
+}
+
+// The daemon's own errors are in the response's result, not in the Err
+fn rpc_result<T: RpcResponseArgument>(result: RpcResult<RpcResponse<T>>) -> Result<(), String> {
+    match result {
+        Ok(response) if response.is_ok() => Ok(()),
+        Ok(response) => Err(response.result),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// A failing torrent doesn't stop the rest, they're all listed at the end.
+async fn add_torrents(
+    ctx: &app::Ctx,
+    torrents: Vec<String>,
+    directory: Option<String>,
+    paused: bool,
+) -> Result<(), String> {
+    let mut added = 0;
+    let mut failures = vec![];
+    for torrent in torrents {
+        match add_torrent(ctx, &torrent, directory.clone(), paused).await {
+            Ok(()) => added += 1,
+            Err(msg) => failures.push(msg),
+        }
+    }
+
+    if added > 0 {
+        ctx.activity.refresh();
+    }
+    if failures.is_empty() {
+        ctx.send_action(Action::ShowStatus(format!("Added {added}")));
+        Ok(())
+    } else {
+        let error_popup = Box::new(ErrorPopup::new(
+            "Failed to add some torrents",
+            failures.join("\n\n"),
+        ));
+        ctx.send_action(Action::Error(error_popup));
+        Err(format!("Added {added}, {} failed", failures.len()))
+    }
+}
+
+// One magnet/URL/path per line, skipping blank lines and # comments
+pub fn parse_torrent_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+// A .torrent file is binary, so a file that reads as text is taken to be a
+// list of torrents instead.
+fn read_torrent_list(torrent: &str) -> Option<Vec<String>> {
+    if is_link(torrent) {
+        return None;
+    }
+    let text = std::fs::read_to_string(expand_tilde(torrent)).ok()?;
+    Some(parse_torrent_list(&text))
+}
+
+// Links are passed to the daemon as they are, while local .torrent files
+// have to be sent as base64-encoded metainfo.
+pub fn torrent_add_args(
+    torrent: &str,
+    directory: Option<String>,
+    paused: bool,
+) -> Result<TorrentAddArgs, String> {
+    if is_link(torrent) {
+        return Ok(TorrentAddArgs {
+            filename: Some(torrent.to_string()),
+            download_dir: directory,
+            paused: Some(paused),
+            ..Default::default()
+        });
+    }
+
+    let path = expand_tilde(torrent);
+    if !path.is_file() {
+        return Err(format!(
+            "\"{torrent}\"\nis neither a magnet link nor an existing torrent file"
+        ));
+    }
+
+    let buf =
+        std::fs::read(&path).map_err(|e| format!("Failed to read \"{}\":\n{e}", path.display()))?;
+    let metainfo = base64::engine::general_purpose::STANDARD.encode(buf);
+
+    Ok(TorrentAddArgs {
+        metainfo: Some(metainfo),
+        download_dir: directory,
+        paused: Some(paused),
+        ..Default::default()
+    })
+}
+
+fn is_link(torrent: &str) -> bool {
+    torrent.starts_with("magnet:") || torrent.starts_with("http:") || torrent.starts_with("https:")
+}
+
+fn magnet_link(torrent: &Torrent) -> String {
+    let hash = torrent.hash_string.as_deref().unwrap_or_default();
+    let mut link = format!("magnet:?xt=urn:btih:{hash}");
+    if let Some(name) = &torrent.name {
+        link.push_str("&dn=");
+        link.extend(url::form_urlencoded::byte_serialize(name.as_bytes()));
+    }
+    link
+}
+
+fn copy_to_clipboard(
+    clipboard: &mut Option<Clipboard>,
+    text: String,
+) -> Result<(), arboard::Error> {
+    if clipboard.is_none() {
+        *clipboard = Some(Clipboard::new()?);
+    }
+    clipboard.as_mut().unwrap().set_text(text)
+}
+
+fn expand_tilde(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(home) = std::env::var_os("HOME") {
+            return PathBuf::from(home).join(rest);
+        }
+    }
+    PathBuf::from(path)
+}
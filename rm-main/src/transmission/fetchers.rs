@@ -1,93 +1,358 @@
 use std::{
+    collections::HashSet,
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 
-use transmission_rpc::types::{FreeSpace, SessionStats, TorrentGetField};
+use rm_config::Header;
+use tokio::sync::oneshot;
+use transmission_rpc::types::{
+    FreeSpace, Id, SessionStats, Torrent, TorrentGetField, TorrentStatus,
+};
 
+use super::{action::TorrentAction, raw_rpc::RawClient, recently_active::RecentlyActiveClient};
 use crate::{
     action::Action,
-    app,
-    ui::tabs::torrents::{rustmission_torrent::RustmissionTorrent, table_manager::TableManager},
+    app, transmission,
+    ui::{
+        global_popups::ErrorPopup,
+        tabs::torrents::{
+            bottom_stats::SpeedHistory, rustmission_torrent::RustmissionTorrent,
+            table_manager::TableManager,
+        },
+    },
 };
 
-pub async fn stats(ctx: app::Ctx, stats: Arc<Mutex<Option<SessionStats>>>) {
+const MIN_RETRY_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+// Doubles the wait between retries while the daemon stays unreachable.
+struct Backoff {
+    delay: Duration,
+}
+
+impl Backoff {
+    const fn new() -> Self {
+        Self {
+            delay: MIN_RETRY_DELAY,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.delay = MIN_RETRY_DELAY;
+    }
+
+    fn next_delay(&mut self) -> Duration {
+        let delay = self.delay;
+        self.delay = (self.delay * 2).min(MAX_RETRY_DELAY);
+        delay
+    }
+}
+
+// Only the first failing fetch shows an error, later ones just retry.
+fn connection_failed(ctx: &app::Ctx, error: &(dyn std::error::Error + 'static)) {
+    if ctx.connection.lose() {
+        let error_popup = ErrorPopup::new(
+            "Lost connection to Transmission",
+            format!(
+                "{}\nRetrying in the background.",
+                transmission::utils::describe_error(error)
+            ),
+        );
+        ctx.send_action(Action::Error(Box::new(error_popup)));
+    }
+}
+
+// A password printed by password_cmd may have changed in the meantime. It's
+// run again only once the daemon turned the current one down.
+async fn rebuild_client(ctx: &app::Ctx, error: &(dyn std::error::Error + Send + Sync + 'static)) {
+    let server = ctx.connection.server();
+    if server.password_cmd.is_none() || !transmission::utils::is_auth_failure(error) {
+        return;
+    }
+    let Ok(auth) = transmission::utils::credentials(&server).await else {
+        return;
+    };
+    if let Ok(client) = transmission::utils::client_with_auth(&server, auth.clone()) {
+        *ctx.client.lock().await = client;
+        ctx.connection.set_auth(auth);
+    }
+}
+
+fn connection_succeeded(ctx: &app::Ctx) {
+    if ctx.connection.regain() {
+        ctx.send_action(Action::ShowStatus(
+            "Reconnected to Transmission".to_string(),
+        ));
+        // Repopulate everything instead of waiting for each fetcher's turn
+        ctx.activity.refresh();
+    }
+}
+
+pub async fn stats(
+    ctx: app::Ctx,
+    stats: Arc<Mutex<Option<SessionStats>>>,
+    speed_history: Arc<Mutex<SpeedHistory>>,
+    alt_speed_enabled: Arc<Mutex<bool>>,
+    refresh_interval: Duration,
+) {
+    let mut backoff = Backoff::new();
+    let mut raw_client: Option<RawClient> = None;
     loop {
-        let new_stats = ctx
-            .client
+        let response = ctx.client.lock().await.session_stats().await;
+        let new_stats = match response {
+            Ok(response) => response.arguments,
+            Err(e) => {
+                connection_failed(&ctx, &*e);
+                ctx.send_action(Action::Render);
+                ctx.activity.wait(backoff.next_delay()).await;
+                continue;
+            }
+        };
+        backoff.reset();
+        connection_succeeded(&ctx);
+
+        speed_history
             .lock()
-            .await
-            .session_stats()
-            .await
             .unwrap()
-            .arguments;
+            .push(new_stats.download_speed, new_stats.upload_speed);
         *stats.lock().unwrap() = Some(new_stats);
+
+        // Also catches it being turned on or off from elsewhere, e.g. on a
+        // schedule or in another client
+        if raw_client.is_none() {
+            raw_client = RawClient::new(&ctx.connection.server(), ctx.connection.auth()).ok();
+        }
+        if let Some(client) = raw_client.as_mut() {
+            match client.session_limits().await {
+                Ok(limits) => *alt_speed_enabled.lock().unwrap() = limits.alt_speed_enabled,
+                Err(_) => raw_client = None,
+            }
+        }
         ctx.send_action(Action::Render);
-        tokio::time::sleep(Duration::from_secs(ctx.config.connection.stats_refresh)).await;
+        ctx.activity.wait(refresh_interval).await;
     }
 }
 
+// Leaves the free space empty (and the stats bar without it) when the
+// download directory is unknown or the daemon doesn't support the call.
 pub async fn free_space(ctx: app::Ctx, free_space: Arc<Mutex<Option<FreeSpace>>>) {
-    let download_dir = ctx
-        .client
-        .lock()
-        .await
-        .session_get()
-        .await
-        .unwrap()
-        .arguments
-        .download_dir
-        .leak();
+    let download_dir = ctx.session_info().download_dir.clone();
+    if download_dir.is_empty() {
+        return;
+    }
 
+    let mut backoff = Backoff::new();
     loop {
-        let new_free_space = ctx
+        let response = ctx
             .client
             .lock()
             .await
-            .free_space(download_dir.to_string())
-            .await
-            .unwrap()
-            .arguments;
-        *free_space.lock().unwrap() = Some(new_free_space);
+            .free_space(download_dir.clone())
+            .await;
+
+        let new_free_space = match response {
+            Ok(response) if response.is_ok() => Some(response.arguments),
+            Ok(_) => None,
+            // The stats and torrents fetchers report the connection
+            Err(_) => {
+                ctx.activity.wait(backoff.next_delay()).await;
+                continue;
+            }
+        };
+        backoff.reset();
+        let is_supported = new_free_space.is_some();
+        *free_space.lock().unwrap() = new_free_space;
         ctx.send_action(Action::Render);
-        tokio::time::sleep(Duration::from_secs(
-            ctx.config.connection.free_space_refresh,
-        ))
-        .await;
+        if !is_supported {
+            return;
+        }
+
+        ctx.activity.wait(ctx.config.free_space_refresh()).await;
     }
 }
 
-pub async fn torrents(ctx: app::Ctx, table_manager: Arc<Mutex<TableManager>>) {
+// The thresholds are in minutes and ratios, no need to look more often
+const AUTO_REMOVE_INTERVAL: Duration = Duration::from_secs(60);
+
+pub async fn auto_remove(ctx: app::Ctx) {
+    if !ctx.config.auto_remove.is_active() {
+        return;
+    }
+
+    let fields = vec![
+        TorrentGetField::Id,
+        TorrentGetField::Name,
+        TorrentGetField::Status,
+        TorrentGetField::LeftUntilDone,
+        TorrentGetField::UploadRatio,
+        TorrentGetField::ActivityDate,
+    ];
+    // In case the daemon still lists them at the next check
+    let mut removed = HashSet::new();
     loop {
-        let fields = vec![
-            TorrentGetField::Id,
-            TorrentGetField::Name,
-            TorrentGetField::IsFinished,
-            TorrentGetField::IsStalled,
-            TorrentGetField::PercentDone,
-            TorrentGetField::UploadRatio,
-            TorrentGetField::SizeWhenDone,
-            TorrentGetField::Eta,
-            TorrentGetField::RateUpload,
-            TorrentGetField::RateDownload,
-            TorrentGetField::Status,
-        ];
-        let rpc_response = ctx
+        let response = ctx
             .client
             .lock()
             .await
-            .torrent_get(Some(fields), None)
+            .torrent_get(Some(fields.clone()), None)
+            .await;
+        // The stats and torrents fetchers report the connection
+        let torrents = response
+            .map(|response| response.arguments.torrents)
+            .unwrap_or_default();
+
+        for torrent in torrents {
+            let Some(id) = torrent.id else {
+                continue;
+            };
+            if !removed.contains(&id) && is_due_for_removal(&ctx, &torrent) {
+                let name = torrent.name.unwrap_or_default();
+                let (removed_tx, removed_rx) = oneshot::channel();
+                ctx.send_torrent_action(TorrentAction::AutoRemove(Id::Id(id), name, removed_tx));
+                // A failed removal is tried again at the next check
+                if removed_rx.await.is_ok() {
+                    removed.insert(id);
+                }
+            }
+        }
+
+        // Not paused while idle, nobody being around is when it matters most
+        tokio::time::sleep(AUTO_REMOVE_INTERVAL).await;
+    }
+}
+
+fn is_due_for_removal(ctx: &app::Ctx, torrent: &Torrent) -> bool {
+    // Torrents still downloading (or checking what they have) are never removed
+    let is_downloaded = torrent.left_until_done == Some(0)
+        && matches!(
+            torrent.status,
+            Some(TorrentStatus::Seeding | TorrentStatus::QueuedToSeed | TorrentStatus::Stopped)
+        );
+    if !is_downloaded {
+        return false;
+    }
+
+    // Transmission's "not available" and "infinite" ratios are negative
+    let ratio = torrent.upload_ratio.unwrap_or_default();
+    let idle = torrent
+        .activity_date
+        .and_then(|date| {
+            let last_active =
+                SystemTime::UNIX_EPOCH + Duration::from_secs(u64::try_from(date.timestamp()).ok()?);
+            SystemTime::now().duration_since(last_active).ok()
+        })
+        .unwrap_or_default();
+    ctx.config.auto_remove.is_due(ratio, idle)
+}
+
+// Sorting, filtering and the actions on the highlighted torrent need most of
+// the fields whether their column is shown or not. The info popup and the
+// details pane fetch the whole torrent on their own.
+fn torrent_fields(columns: &[Header]) -> Vec<TorrentGetField> {
+    let mut fields = vec![
+        TorrentGetField::Id,
+        TorrentGetField::Name,
+        TorrentGetField::PercentDone,
+        TorrentGetField::SizeWhenDone,
+        TorrentGetField::RateUpload,
+        TorrentGetField::RateDownload,
+        TorrentGetField::Status,
+        TorrentGetField::Error,
+        TorrentGetField::ErrorString,
+        TorrentGetField::DownloadDir,
+        TorrentGetField::QueuePosition,
+        TorrentGetField::Labels,
+        // Exported even without its column
+        TorrentGetField::UploadRatio,
+    ];
+    if columns.contains(&Header::Eta) {
+        fields.push(TorrentGetField::Eta);
+    }
+    if columns.contains(&Header::Peers) {
+        fields.push(TorrentGetField::PeersSendingToUs);
+        fields.push(TorrentGetField::PeersGettingFromUs);
+    }
+    fields
+}
+
+// Every this many fetches all torrents are fetched again, in case the daemon
+// left something out of the recently active ones.
+const FULL_FETCH_EVERY: u32 = 12;
+
+// After a full fetch, only the recently active torrents are fetched and merged
+// into the table. Any failure there goes back to a full fetch right away.
+pub async fn torrents(
+    ctx: app::Ctx,
+    table_manager: Arc<Mutex<TableManager>>,
+    refresh_interval: Duration,
+) {
+    let mut backoff = Backoff::new();
+    let mut recently_active: Option<RecentlyActiveClient> = None;
+    let mut fetches_since_full = 0;
+    let fields = torrent_fields(&ctx.config.torrents_tab.columns);
+    let units = ctx.config.units();
+    loop {
+        if let Some(client) = recently_active
+            .as_mut()
+            .filter(|_| fetches_since_full < FULL_FETCH_EVERY)
+        {
+            match client.fetch(&fields).await {
+                Ok(changes) => {
+                    fetches_since_full += 1;
+                    ctx.connection.updated();
+                    table_manager.lock().unwrap().apply_changes(
+                        changes
+                            .torrents
+                            .iter()
+                            .map(|torrent| RustmissionTorrent::new(torrent, units))
+                            .collect(),
+                        &changes.removed,
+                    );
+                    ctx.send_action(Action::TorrentsFetched);
+                    ctx.activity.wait(refresh_interval).await;
+                    continue;
+                }
+                Err(_) => recently_active = None,
+            }
+        }
+
+        let rpc_response = ctx
+            .client
+            .lock()
             .await
-            .unwrap();
+            .torrent_get(Some(fields.clone()), None)
+            .await;
 
-        let new_torrents = rpc_response.arguments.torrents;
+        let new_torrents = match rpc_response {
+            Ok(rpc_response) => rpc_response.arguments.torrents,
+            Err(e) => {
+                connection_failed(&ctx, &*e);
+                rebuild_client(&ctx, &*e).await;
+                ctx.send_action(Action::Render);
+                ctx.activity.wait(backoff.next_delay()).await;
+                continue;
+            }
+        };
+        backoff.reset();
+        connection_succeeded(&ctx);
+        ctx.connection.updated();
+        fetches_since_full = 0;
+        if recently_active.is_none() {
+            recently_active =
+                RecentlyActiveClient::new(&ctx.connection.server(), ctx.connection.auth()).ok();
+        }
 
         {
             let mut table_manager_lock = table_manager.lock().unwrap();
-            table_manager_lock
-                .set_new_rows(new_torrents.iter().map(RustmissionTorrent::from).collect());
+            table_manager_lock.set_new_rows(
+                new_torrents
+                    .iter()
+                    .map(|torrent| RustmissionTorrent::new(torrent, units))
+                    .collect(),
+            );
         }
-        ctx.send_action(Action::Render);
-        tokio::time::sleep(Duration::from_secs(ctx.config.connection.torrents_refresh)).await;
+        ctx.send_action(Action::TorrentsFetched);
+        ctx.activity.wait(refresh_interval).await;
     }
 }
@@ -1,5 +1,9 @@
 mod action;
 pub mod fetchers;
+mod raw_rpc;
+mod recently_active;
 pub mod utils;
 
-pub use action::{action_handler, TorrentAction};
+pub use raw_rpc::SessionLimits;
+
+pub use action::{action_handler, parse_torrent_list, torrent_add_args, TorrentAction};
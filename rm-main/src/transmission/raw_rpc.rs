@@ -0,0 +1,110 @@
+use anyhow::{bail, Result};
+use reqwest::StatusCode;
+use rm_config::Server;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use transmission_rpc::types::BasicAuth;
+
+use crate::transmission;
+
+const SESSION_ID_HEADER: &str = "X-Transmission-Session-Id";
+
+// For what transmission-rpc can't send or leaves out of the response. Takes
+// the credentials the main client was built with, so password_cmd isn't run
+// again for it.
+pub struct RawClient {
+    http_client: reqwest::Client,
+    url: String,
+    auth: BasicAuth,
+    session_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Request<'a, A> {
+    method: &'static str,
+    arguments: &'a A,
+}
+
+#[derive(Deserialize)]
+struct Response<R> {
+    result: String,
+    arguments: Option<R>,
+}
+
+#[derive(Serialize)]
+struct SessionGetArguments {
+    fields: &'static [&'static str],
+}
+
+// What transmission-rpc's SessionGet doesn't have. The limits are in kB/s.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SessionLimits {
+    pub alt_speed_enabled: bool,
+    pub speed_limit_down: u64,
+    pub speed_limit_down_enabled: bool,
+    pub speed_limit_up: u64,
+    pub speed_limit_up_enabled: bool,
+}
+
+impl RawClient {
+    pub fn new(server: &Server, auth: BasicAuth) -> Result<Self> {
+        Ok(Self {
+            http_client: transmission::utils::http_client(server)?,
+            url: server.url.clone(),
+            auth,
+            session_id: None,
+        })
+    }
+
+    pub async fn call<A: Serialize, R: DeserializeOwned>(
+        &mut self,
+        method: &'static str,
+        arguments: &A,
+    ) -> Result<R> {
+        let request = Request { method, arguments };
+
+        // The first request only gets us a session id, like in transmission-rpc
+        for _ in 0..2 {
+            let mut builder = self
+                .http_client
+                .post(&self.url)
+                .basic_auth(&self.auth.user, Some(&self.auth.password))
+                .json(&request);
+            if let Some(session_id) = &self.session_id {
+                builder = builder.header(SESSION_ID_HEADER, session_id);
+            }
+
+            let response = builder.send().await?;
+            if response.status() == StatusCode::CONFLICT {
+                let session_id = response
+                    .headers()
+                    .get(SESSION_ID_HEADER)
+                    .and_then(|session_id| session_id.to_str().ok());
+                self.session_id = session_id.map(str::to_string);
+                continue;
+            }
+
+            let response: Response<R> = response.error_for_status()?.json().await?;
+            let Some(arguments) = response.arguments.filter(|_| response.result == "success")
+            else {
+                bail!("{method} failed: {}", response.result);
+            };
+            return Ok(arguments);
+        }
+
+        bail!("the daemon didn't accept the session id")
+    }
+
+    pub async fn session_limits(&mut self) -> Result<SessionLimits> {
+        let arguments = SessionGetArguments {
+            fields: &[
+                "alt-speed-enabled",
+                "speed-limit-down",
+                "speed-limit-down-enabled",
+                "speed-limit-up",
+                "speed-limit-up-enabled",
+            ],
+        };
+        self.call("session-get", &arguments).await
+    }
+}
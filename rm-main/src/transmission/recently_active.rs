@@ -0,0 +1,52 @@
+use anyhow::Result;
+use rm_config::Server;
+use serde::{Deserialize, Serialize};
+use transmission_rpc::types::{BasicAuth, Torrent, TorrentGetField};
+
+use super::raw_rpc::RawClient;
+
+// transmission-rpc only takes a list of ids, so torrent-get with
+// ids = "recently-active" is sent by hand. The daemon then answers with just
+// the torrents that changed lately and the ids of removed ones.
+pub struct RecentlyActiveClient {
+    client: RawClient,
+}
+
+#[derive(Serialize)]
+struct RequestArguments<'a> {
+    fields: &'a [String],
+    ids: &'static str,
+}
+
+#[derive(Deserialize)]
+struct ResponseArguments {
+    torrents: Vec<Torrent>,
+    #[serde(default)]
+    removed: Vec<i64>,
+}
+
+pub struct Changes {
+    pub torrents: Vec<Torrent>,
+    pub removed: Vec<i64>,
+}
+
+impl RecentlyActiveClient {
+    pub fn new(server: &Server, auth: BasicAuth) -> Result<Self> {
+        Ok(Self {
+            client: RawClient::new(server, auth)?,
+        })
+    }
+
+    pub async fn fetch(&mut self, fields: &[TorrentGetField]) -> Result<Changes> {
+        let fields: Vec<_> = fields.iter().map(TorrentGetField::to_str).collect();
+        let arguments = RequestArguments {
+            fields: &fields,
+            ids: "recently-active",
+        };
+        let arguments: ResponseArguments = self.client.call("torrent-get", &arguments).await?;
+        Ok(Changes {
+            torrents: arguments.torrents,
+            removed: arguments.removed,
+        })
+    }
+}
@@ -1,22 +1,69 @@
-use rm_config::Config;
+use anyhow::Result;
+use rm_config::{Config, Server};
 use transmission_rpc::{types::BasicAuth, TransClient};
 
-pub fn client_from_config(config: &Config) -> TransClient {
-    let user = config
-        .connection
-        .username
-        .as_ref()
-        .unwrap_or(&"".to_string())
-        .clone();
-    let password = config
-        .connection
-        .password
-        .as_ref()
-        .unwrap_or(&"".to_string())
-        .clone();
-    let url = config.connection.url.parse().unwrap();
-
-    let auth = BasicAuth { user, password };
-
-    TransClient::with_auth(url, auth)
+pub async fn client_from_config(config: &Config) -> Result<TransClient> {
+    client_from_server(&config.servers()[0]).await
+}
+
+pub async fn client_from_server(server: &Server) -> Result<TransClient> {
+    let auth = credentials(server).await?;
+    client_with_auth(server, auth)
+}
+
+// password_cmd can take a while, e.g. to unlock a keyring, so it gets a
+// thread of its own instead of holding up an async worker
+pub async fn credentials(server: &Server) -> Result<BasicAuth> {
+    let user = server.username.clone().unwrap_or_default();
+    let password = {
+        let server = server.clone();
+        tokio::task::spawn_blocking(move || server.password()).await??
+    };
+    Ok(BasicAuth {
+        user,
+        password: password.unwrap_or_default(),
+    })
+}
+
+pub fn client_with_auth(server: &Server, auth: BasicAuth) -> Result<TransClient> {
+    let url = server.url.parse().unwrap();
+
+    let mut client = TransClient::new_with_client(url, http_client(server)?);
+    client.set_auth(auth);
+
+    Ok(client)
+}
+
+// transmission-rpc doesn't say what status the daemon answered with, but a
+// wrong password gets an HTML page back, which fails to decode as JSON
+pub fn is_auth_failure(error: &(dyn std::error::Error + 'static)) -> bool {
+    error
+        .downcast_ref::<reqwest::Error>()
+        .is_some_and(reqwest::Error::is_decode)
+}
+
+pub fn http_client(server: &Server) -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .danger_accept_invalid_certs(server.accept_invalid_certs)
+        .build()?)
+}
+
+// reqwest only says it failed to send the request, the reason is further
+// down the chain.
+pub fn describe_error(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message = format!("{message}: {cause}");
+        source = cause.source();
+    }
+
+    if message.to_lowercase().contains("certificate") {
+        message.push_str(
+            "\nIf the daemon uses a self-signed certificate, set accept_invalid_certs = true \
+            in the config. It turns off certificate checks, so anyone between you and the \
+            daemon could read and alter the traffic, password included.",
+        );
+    }
+    message
 }
@@ -3,7 +3,10 @@ use std::time::Duration;
 use anyhow::Result;
 use crossterm::{
     cursor,
-    event::{Event as CrosstermEvent, KeyEvent, KeyEventKind},
+    event::{
+        DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent, MouseEventKind,
+    },
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures::{FutureExt, StreamExt};
@@ -20,6 +23,9 @@ pub enum Event {
     Error,
     Render,
     Key(KeyEvent),
+    Mouse(MouseEvent),
+    // Goes to the open input as a whole, never mapped to actions key by key
+    Paste(String),
 }
 
 pub struct Tui {
@@ -28,10 +34,11 @@ pub struct Tui {
     pub cancellation_token: CancellationToken,
     pub event_rx: UnboundedReceiver<Event>,
     pub event_tx: UnboundedSender<Event>,
+    mouse: bool,
 }
 
 impl Tui {
-    pub(crate) fn new() -> Result<Self> {
+    pub(crate) fn new(mouse: bool) -> Result<Self> {
         let terminal = ratatui::Terminal::new(Backend::new(std::io::stdout()))?;
         let (event_tx, event_rx) = mpsc::unbounded_channel();
         let cancellation_token = CancellationToken::new();
@@ -42,6 +49,7 @@ impl Tui {
             cancellation_token,
             event_rx,
             event_tx,
+            mouse,
         })
     }
 
@@ -68,9 +76,24 @@ impl Tui {
         event_tx: &UnboundedSender<Event>,
     ) {
         match event {
-            Some(Ok(CrosstermEvent::Key(key))) => {
-                if key.kind == KeyEventKind::Press {
-                    event_tx.send(Event::Key(key)).unwrap();
+            Some(Ok(CrosstermEvent::Key(key))) if key.kind == KeyEventKind::Press => {
+                event_tx.send(Event::Key(key)).unwrap();
+            }
+            // Mouse movement isn't used, so don't wake up the app for it
+            Some(Ok(CrosstermEvent::Mouse(mouse)))
+                if matches!(
+                    mouse.kind,
+                    MouseEventKind::Down(_) | MouseEventKind::ScrollDown | MouseEventKind::ScrollUp
+                ) =>
+            {
+                event_tx.send(Event::Mouse(mouse)).unwrap();
+            }
+            Some(Ok(CrosstermEvent::Paste(text))) => {
+                // A copied line often ends with a newline, which isn't part
+                // of the magnet or path in it
+                let text = text.trim_end_matches(['\r', '\n']);
+                if !text.is_empty() {
+                    event_tx.send(Event::Paste(text.to_string())).unwrap();
                 }
             }
             Some(Ok(CrosstermEvent::Resize(_, _))) => event_tx.send(Event::Render).unwrap(),
@@ -96,7 +119,15 @@ impl Tui {
 
     pub(crate) fn enter(&mut self) -> Result<()> {
         crossterm::terminal::enable_raw_mode()?;
-        crossterm::execute!(std::io::stdout(), EnterAlternateScreen, cursor::Hide)?;
+        crossterm::execute!(
+            std::io::stdout(),
+            EnterAlternateScreen,
+            EnableBracketedPaste,
+            cursor::Hide
+        )?;
+        if self.mouse {
+            crossterm::execute!(std::io::stdout(), EnableMouseCapture)?;
+        }
         self.start();
         Ok(())
     }
@@ -105,7 +136,15 @@ impl Tui {
         self.stop();
         if crossterm::terminal::is_raw_mode_enabled()? {
             self.terminal.flush()?;
-            crossterm::execute!(std::io::stdout(), LeaveAlternateScreen, cursor::Show)?;
+            if self.mouse {
+                crossterm::execute!(std::io::stdout(), DisableMouseCapture)?;
+            }
+            crossterm::execute!(
+                std::io::stdout(),
+                DisableBracketedPaste,
+                LeaveAlternateScreen,
+                cursor::Show
+            )?;
             crossterm::terminal::disable_raw_mode()?;
         }
         Ok(())
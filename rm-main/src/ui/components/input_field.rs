@@ -0,0 +1,112 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Clear, Paragraph},
+};
+use tui_input::{Input, InputRequest};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    action::Action,
+    app,
+    ui::{components::Component, pasted_chars},
+};
+
+// A single line of text after a prompt. See to_input_request for the
+// editing keys every one of them understands.
+pub struct InputField {
+    input: Input,
+    prompt: String,
+    // Over the whole field, the prompt included
+    style: Style,
+    // Whether the cursor is placed in the field
+    is_focused: bool,
+    ctx: app::Ctx,
+}
+
+impl InputField {
+    pub fn new(ctx: app::Ctx, prompt: String) -> Self {
+        Self {
+            ctx,
+            prompt,
+            style: Style::default(),
+            is_focused: true,
+            input: Input::default(),
+        }
+    }
+
+    pub fn new_with_value(ctx: app::Ctx, prompt: String, value: String) -> Self {
+        Self {
+            input: Input::default().with_value(value),
+            ..Self::new(ctx, prompt)
+        }
+    }
+
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    pub fn set_focused(&mut self, is_focused: bool) {
+        self.is_focused = is_focused;
+    }
+
+    pub fn text(&self) -> String {
+        self.input.to_string()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.input.value().is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.input.reset();
+    }
+
+    pub fn handle(&mut self, req: InputRequest) {
+        self.input.handle(req);
+    }
+
+    // Inserted at the cursor all at once
+    pub fn paste(&mut self, text: &str) {
+        for char in pasted_chars(text) {
+            self.input.handle(InputRequest::InsertChar(char));
+        }
+    }
+}
+
+impl Component for InputField {
+    fn handle_actions(&mut self, _action: Action) -> Option<Action> {
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, rect: Rect) {
+        f.render_widget(Clear, rect);
+
+        let prompt_width = u16::try_from(self.prompt.width()).unwrap_or(u16::MAX);
+        let [prompt_rect, text_rect] =
+            Layout::horizontal([Constraint::Length(prompt_width), Constraint::Min(1)]).areas(rect);
+
+        let prompt_style = Style::default()
+            .fg(self.ctx.config.general.accent_color)
+            .patch(self.style);
+        let prompt = Paragraph::new(self.prompt.as_str()).style(prompt_style);
+        f.render_widget(prompt, prompt_rect);
+
+        // Text too long for the field scrolls so the cursor stays in sight,
+        // with a column left for it after the last character
+        let width = usize::from(text_rect.width.saturating_sub(1));
+        let scroll = self.input.visual_scroll(width);
+        let text = Paragraph::new(self.input.value())
+            .style(self.style)
+            .scroll((0, u16::try_from(scroll).unwrap_or(u16::MAX)));
+        f.render_widget(text, text_rect);
+
+        if !self.is_focused {
+            return;
+        }
+        let cursor_offset = self.input.visual_cursor() - scroll;
+        f.set_cursor(
+            text_rect.x + u16::try_from(cursor_offset).unwrap_or(u16::MAX),
+            text_rect.y,
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod input_field;
 pub mod table;
 pub mod tabs;
 
@@ -37,70 +37,61 @@ impl<T: Clone> GenericTable<T> {
         items.get(selected).cloned()
     }
 
-    pub fn next(&mut self) {
-        let mut state = self.state.borrow_mut();
-        if let Some(curr) = state.selected() {
-            let last_idx = self.get_len() - 1;
-            if curr == last_idx {
-                state.select(Some(0));
-            } else {
-                state.select(Some(curr + 1));
-            }
-        }
+    // Moving past the last row goes back to the first one only with `wrap`
+    pub fn next(&mut self, wrap: bool) {
+        self.scroll_down_by(1, wrap);
     }
 
-    pub fn previous(&mut self) {
-        let mut state = self.state.borrow_mut();
-
-        if let Some(curr) = state.selected() {
-            let last_idx = self.get_len() - 1;
-            if curr == 0 {
-                state.select(Some(last_idx));
-            } else {
-                state.select(Some(curr - 1));
-            }
-        }
+    pub fn previous(&mut self, wrap: bool) {
+        self.scroll_up_by(1, wrap);
     }
 
-    pub fn scroll_down_by(&mut self, amount: usize) {
-        if self.items.is_empty() {
+    // With `wrap`, scrolling down from the last row goes to the first one.
+    // Otherwise, and from any other row, it stops at the last row.
+    pub fn scroll_down_by(&mut self, amount: usize, wrap: bool) {
+        if self.get_len() == 0 {
             return;
         }
 
         let mut state = self.state.borrow_mut();
-        let new_selection = state.selected().unwrap_or_default() + amount;
+        let selected = state.selected().unwrap_or_default();
+        let last_idx = self.get_len() - 1;
 
-        if new_selection > self.get_len() {
-            state.select(Some(self.get_len() - 1));
+        if wrap && selected == last_idx {
+            state.select(Some(0));
         } else {
-            state.select(Some(new_selection));
-        };
+            state.select(Some((selected + amount).min(last_idx)));
+        }
     }
 
-    pub fn scroll_up_by(&mut self, amount: usize) {
+    pub fn scroll_up_by(&mut self, amount: usize, wrap: bool) {
+        if self.get_len() == 0 {
+            return;
+        }
+
         let mut state = self.state.borrow_mut();
         let selected = state.selected().unwrap_or_default();
 
-        if amount >= selected {
-            state.select(Some(0));
+        if wrap && selected == 0 {
+            state.select(Some(self.get_len() - 1));
         } else {
-            state.select(Some(selected - amount));
+            state.select(Some(selected.saturating_sub(amount)));
         }
     }
 
     pub fn scroll_to_home(&mut self) {
         let mut state = self.state.borrow_mut();
-        if !self.items.is_empty() {
+        if self.get_len() != 0 {
             state.select(Some(0));
         }
     }
 
     pub fn scroll_to_end(&mut self) {
-        if self.items.is_empty() {
+        if self.get_len() == 0 {
             return;
         }
 
         let mut state = self.state.borrow_mut();
-        state.select(Some(self.items.len() - 1));
+        state.select(Some(self.get_len() - 1));
     }
 }
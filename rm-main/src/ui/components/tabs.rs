@@ -1,34 +1,83 @@
-use crate::{action::Action, app};
+use rm_config::keymap::{GeneralAction, Tab};
+
+use crate::{action::Action, app, ui::state::UiState};
 
 use super::Component;
 use ratatui::{layout::Flex, prelude::*, widgets::Tabs};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum CurrentTab {
-    Torrents = 0,
+    Torrents,
     Search,
 }
 
+impl CurrentTab {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Torrents => "Torrents",
+            Self::Search => "Search",
+        }
+    }
+
+    const fn switch_action(self) -> GeneralAction {
+        match self {
+            Self::Torrents => GeneralAction::SwitchToTorrents,
+            Self::Search => GeneralAction::SwitchToSearch,
+        }
+    }
+}
+
+impl From<Tab> for CurrentTab {
+    fn from(tab: Tab) -> Self {
+        match tab {
+            Tab::Torrents => Self::Torrents,
+            Tab::Search => Self::Search,
+        }
+    }
+}
+
 pub struct TabComponent {
-    tabs_list: [&'static str; 2],
+    // As ordered in general.tabs, never empty
+    tabs: Vec<CurrentTab>,
     pub current_tab: CurrentTab,
     ctx: app::Ctx,
 }
 
 impl TabComponent {
     pub fn new(ctx: app::Ctx) -> Self {
-        let tabs_list = {
-            if ctx.config.general.beginner_mode {
-                ["1. Torrents", "2. Search"]
-            } else {
-                ["Torrents", "Search"]
-            }
-        };
+        let tabs: Vec<_> = ctx
+            .config
+            .general
+            .tabs
+            .iter()
+            .copied()
+            .map(CurrentTab::from)
+            .collect();
+
+        // A saved index that's out of range goes back to the first tab
+        let current_tab = tabs.get(UiState::load().tab).copied().unwrap_or(tabs[0]);
 
         Self {
             ctx,
-            tabs_list,
-            current_tab: CurrentTab::Torrents,
+            tabs,
+            current_tab,
+        }
+    }
+
+    fn position(&self) -> usize {
+        self.tabs
+            .iter()
+            .position(|tab| *tab == self.current_tab)
+            .unwrap_or_default()
+    }
+
+    fn switch_to(&mut self, position: usize) {
+        let Some(&tab) = self.tabs.get(position) else {
+            return;
+        };
+        if self.current_tab != tab {
+            self.current_tab = tab;
+            UiState::update(|state| state.tab = position);
         }
     }
 }
@@ -37,40 +86,60 @@ impl Component for TabComponent {
     fn render(&mut self, f: &mut Frame, rect: Rect) {
         let divider = symbols::DOT;
 
-        let tabs_length =
-            self.tabs_list.concat().chars().count() + divider.len() + self.tabs_list.len();
+        // Beginners see the key that switches to each tab, e.g. "1. Torrents"
+        let keymap = self.ctx.config.keymap.read().unwrap();
+        let arrow_glyphs = self.ctx.config.general.arrow_glyphs;
+        let tabs_list: Vec<_> = self
+            .tabs
+            .iter()
+            .map(|tab| {
+                let key = keymap
+                    .general
+                    .keybindings
+                    .iter()
+                    .find(|keybinding| keybinding.action == tab.switch_action())
+                    .filter(|_| self.ctx.config.general.beginner_mode);
+                match key {
+                    Some(key) => format!("{}. {}", key.keycode_string(arrow_glyphs), tab.name()),
+                    None => tab.name().to_string(),
+                }
+            })
+            .collect();
+        drop(keymap);
+
+        // Every title is padded with a space on both sides
+        let tabs_length = tabs_list.concat().chars().count()
+            + divider.chars().count() * (tabs_list.len() - 1)
+            + tabs_list.len() * 2;
 
         let center_rect = Layout::horizontal([Constraint::Length(tabs_length.try_into().unwrap())])
             .flex(Flex::Center)
             .split(rect)[0];
 
         let tabs_highlight_style = Style::default().fg(self.ctx.config.general.accent_color);
-        let tabs = Tabs::new(self.tabs_list)
+        let tabs = Tabs::new(tabs_list)
             .style(Style::default().white())
             .highlight_style(tabs_highlight_style)
-            .select(self.current_tab as usize)
-            .divider(symbols::DOT);
+            .select(self.position())
+            .divider(divider);
 
         f.render_widget(tabs, center_rect);
     }
 
     fn handle_actions(&mut self, action: Action) -> Option<Action> {
         match action {
-            Action::ChangeTab(tab) => match tab {
-                1 => self.current_tab = CurrentTab::Torrents,
-                2 => self.current_tab = CurrentTab::Search,
-                _ => (),
-            },
-            // left only works on right-most tab (search)
-            Action::Left => match self.current_tab {
-                CurrentTab::Search => self.current_tab = CurrentTab::Torrents,
-                _ => (),
-            },
-            // right only works on left-most tab (torrents)
-            Action::Right => match self.current_tab {
-                CurrentTab::Torrents => self.current_tab = CurrentTab::Search,
-                _ => (),
-            },
+            // Counting from 1, like the tabs are numbered
+            Action::ChangeTab(tab) => {
+                if let Some(position) = usize::from(tab).checked_sub(1) {
+                    self.switch_to(position);
+                }
+            }
+            // Both wrap around the ends
+            Action::Left => {
+                let len = self.tabs.len();
+                self.switch_to((self.position() + len - 1) % len);
+            }
+            Action::Right => self.switch_to((self.position() + 1) % self.tabs.len()),
             _ => (),
         }
         None
@@ -13,6 +13,8 @@ pub struct ErrorPopup {
     // TODO: make sure that title always has padding
     title: String,
     message: String,
+    // Errors waiting behind this one
+    queued: usize,
 }
 
 impl ErrorPopup {
@@ -20,8 +22,13 @@ impl ErrorPopup {
         Self {
             title: title.to_owned(),
             message,
+            queued: 0,
         }
     }
+
+    pub fn set_queued(&mut self, queued: usize) {
+        self.queued = queued;
+    }
 }
 
 impl Component for ErrorPopup {
@@ -42,10 +49,15 @@ impl Component for ErrorPopup {
 
         let button = Paragraph::new("[ OK ]").bold().right_aligned();
 
+        let title = match self.queued {
+            0 => format!(" {} ", self.title),
+            1 => format!(" {} (1 more error) ", self.title),
+            queued => format!(" {} ({queued} more errors) ", self.title),
+        };
         let block = Block::bordered()
             .border_set(symbols::border::ROUNDED)
             .title_style(Style::new().red())
-            .title(format!(" {} ", self.title));
+            .title(title);
 
         let error_message = Paragraph::new(&*self.message).wrap(Wrap { trim: false });
 
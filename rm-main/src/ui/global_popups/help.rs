@@ -1,3 +1,4 @@
+use crossterm::event::KeyCode;
 use ratatui::{
     prelude::*,
     widgets::{
@@ -6,10 +7,17 @@ use ratatui::{
     },
 };
 
+use rm_config::keymap::{GeneralAction, Keybinding, TorrentsAction};
+
 use crate::{
     action::Action,
     app,
-    ui::{centered_rect, components::Component},
+    ui::{
+        centered_rect,
+        components::{input_field::InputField, Component},
+        matcher::Matcher,
+        to_input_request,
+    },
 };
 
 macro_rules! add_line {
@@ -22,19 +30,108 @@ macro_rules! add_line {
     };
 }
 
+// Keys bound to the same action are shown on one line. The section is left
+// out if the filter matches none of them.
+fn add_keybindings<T: Copy + PartialEq>(
+    lines: &mut Vec<Line<'_>>,
+    section: &'static str,
+    keybindings: &[Keybinding<T>],
+    desc: fn(T) -> &'static str,
+    arrow_glyphs: bool,
+    matcher: Option<&Matcher>,
+) {
+    let mut actions: Vec<(T, Vec<String>)> = vec![];
+    for keybinding in keybindings {
+        match actions
+            .iter_mut()
+            .find(|(action, _)| *action == keybinding.action)
+        {
+            Some((_, keys)) => keys.push(keybinding.keycode_string(arrow_glyphs)),
+            None => actions.push((
+                keybinding.action,
+                vec![keybinding.keycode_string(arrow_glyphs)],
+            )),
+        }
+    }
+
+    let mut section_lines = vec![];
+    for (action, keys) in actions {
+        let keys = keys.join(" / ");
+        let description = desc(action);
+        let is_match = matcher.is_none_or(|matcher| {
+            matcher
+                .matches(&format!("{keys} - {description}"))
+                .is_some()
+        });
+        if is_match {
+            add_line!(section_lines, keys, description);
+        }
+    }
+
+    if !section_lines.is_empty() {
+        lines.push(
+            Line::from(vec![Span::styled(
+                section,
+                Style::default().bold().underlined(),
+            )])
+            .centered(),
+        );
+        lines.extend(section_lines);
+    }
+}
+
 pub struct HelpPopup {
     ctx: app::Ctx,
+    filter: InputField,
+    is_typing: bool,
 }
 
 impl HelpPopup {
-    pub const fn new(ctx: app::Ctx) -> Self {
-        Self { ctx }
+    pub fn new(ctx: app::Ctx) -> Self {
+        Self {
+            filter: InputField::new(ctx.clone(), "Filter: ".to_string()),
+            ctx,
+            is_typing: false,
+        }
+    }
+
+    fn handle_input(&mut self, input: crossterm::event::KeyEvent) -> Option<Action> {
+        match input.code {
+            KeyCode::Enter => {
+                self.is_typing = false;
+                Some(Action::SwitchToNormalMode)
+            }
+            KeyCode::Esc => {
+                self.is_typing = false;
+                self.filter.clear();
+                Some(Action::SwitchToNormalMode)
+            }
+            _ => {
+                let req = to_input_request(input)?;
+                self.filter.handle(req);
+                Some(Action::Render)
+            }
+        }
     }
 }
 
 impl Component for HelpPopup {
     fn handle_actions(&mut self, action: Action) -> Option<Action> {
         match action {
+            Action::Input(input) if self.is_typing => self.handle_input(input),
+            Action::Paste(text) if self.is_typing => {
+                self.filter.paste(&text);
+                Some(Action::Render)
+            }
+            Action::Search => {
+                self.is_typing = true;
+                Some(Action::SwitchToInputMode)
+            }
+            // The first Esc only clears the filter
+            Action::SoftQuit if !self.filter.is_empty() => {
+                self.filter.clear();
+                Some(Action::Render)
+            }
             action if action.is_soft_quit() => Some(Action::SoftQuit),
             Action::Confirm | Action::ShowHelp => Some(Action::SoftQuit),
             _ => None,
@@ -44,7 +141,12 @@ impl Component for HelpPopup {
     fn render(&mut self, f: &mut Frame, rect: Rect) {
         let centered_rect = centered_rect(rect, 75, 75);
         let popup_rect = centered_rect.inner(&Margin::new(1, 1));
-        let text_rect = popup_rect.inner(&Margin::new(3, 2));
+        let [filter_rect, _, text_rect] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .areas(popup_rect.inner(&Margin::new(3, 1)));
 
         let title_style = Style::new().fg(self.ctx.config.general.accent_color);
         let block = Block::bordered()
@@ -61,43 +163,39 @@ impl Component for HelpPopup {
             .title(" Help ")
             .title_style(title_style);
 
-        let mut lines = vec![Line::from(vec![Span::styled(
-            "Global Keybindings",
-            Style::default().bold().underlined(),
-        )])
-        .centered()];
-
-        add_line!(lines, "? / F1", "show/hide help");
-        add_line!(lines, "q", "quit Rustmission / a popup");
-        add_line!(lines, "ESC", "close a popup / task");
-        add_line!(lines, "1", "switch to torrents tab");
-        add_line!(lines, "2", "switch to search tab");
-        add_line!(lines, "h / ←", "switch to tab left of current tab");
-        add_line!(lines, "l / →", "switch to tab right of current tab");
-        add_line!(lines, "j / ↓", "move down");
-        add_line!(lines, "k / ↑", "move up");
-        add_line!(lines, "/", "search or filter");
-        add_line!(lines, "TAB", "switch focus");
-        add_line!(lines, "Enter", "confirm");
-        add_line!(lines, "CTRL-d", "scroll page down");
-        add_line!(lines, "CTRL-u", "scroll page up");
-        add_line!(lines, "Home", "scroll to the beginning");
-        add_line!(lines, "End", "scroll to the end");
+        // Same matching as the torrents filter
+        let filter = self.filter.text();
+        let matcher = if filter.is_empty() {
+            Ok(None)
+        } else {
+            Matcher::new(self.ctx.config.torrents_tab.filter_mode, &filter).map(Some)
+        };
 
-        lines.push(
-            Line::from(vec![Span::styled(
-                "Torrents Tab",
-                Style::default().bold().underlined(),
-            )])
-            .centered(),
-        );
+        let keymap = self.ctx.config.keymap.read().unwrap();
+        let mut lines = vec![];
+        if let Ok(matcher) = &matcher {
+            add_keybindings(
+                &mut lines,
+                "General",
+                &keymap.general.keybindings,
+                GeneralAction::desc,
+                self.ctx.config.general.arrow_glyphs,
+                matcher.as_ref(),
+            );
+
+            add_keybindings(
+                &mut lines,
+                "Torrents",
+                &keymap.torrents_tab.keybindings,
+                TorrentsAction::desc,
+                self.ctx.config.general.arrow_glyphs,
+                matcher.as_ref(),
+            );
+        }
 
-        add_line!(lines, "a", "add a magnet url");
-        add_line!(lines, "p", "pause/unpause a torrent");
-        add_line!(lines, "d", "delete a torrent without files");
-        add_line!(lines, "D", "delete a torrent with files");
-        add_line!(lines, "f", "show files of a torrent");
-        add_line!(lines, "s", "show statistics");
+        if lines.is_empty() {
+            lines.push(Line::from("No keybindings match").dark_gray().centered());
+        }
 
         let help_text = Text::from(lines);
         let help_paragraph = Paragraph::new(help_text);
@@ -105,5 +203,23 @@ impl Component for HelpPopup {
         f.render_widget(Clear, centered_rect);
         f.render_widget(block, popup_rect);
         f.render_widget(help_paragraph, text_rect);
+
+        if filter.is_empty() && !self.is_typing {
+            f.render_widget(Paragraph::new("Press / to filter").dark_gray(), filter_rect);
+        } else {
+            let invalid_regex = if matcher.is_err() {
+                " (invalid regex)"
+            } else {
+                ""
+            };
+            let [input_rect, invalid_regex_rect] = Layout::horizontal([
+                Constraint::Min(1),
+                Constraint::Length(invalid_regex.len() as u16),
+            ])
+            .areas(filter_rect);
+            self.filter.set_focused(self.is_typing);
+            self.filter.render(f, input_rect);
+            f.render_widget(Paragraph::new(invalid_regex).red(), invalid_regex_rect);
+        }
     }
 }
@@ -1,6 +1,8 @@
 mod error;
 mod help;
 
+use std::collections::VecDeque;
+
 use ratatui::prelude::*;
 
 pub use error::ErrorPopup;
@@ -11,7 +13,8 @@ use crate::{action::Action, app};
 use super::components::Component;
 
 pub(super) struct GlobalPopupManager {
-    pub error_popup: Option<ErrorPopup>,
+    // Shown one at a time, oldest first
+    errors: VecDeque<ErrorPopup>,
     pub help_popup: Option<HelpPopup>,
     ctx: app::Ctx,
 }
@@ -19,14 +22,30 @@ pub(super) struct GlobalPopupManager {
 impl GlobalPopupManager {
     pub fn new(ctx: app::Ctx) -> Self {
         Self {
-            error_popup: None,
+            errors: VecDeque::new(),
             help_popup: None,
             ctx,
         }
     }
 
-    pub const fn needs_action(&self) -> bool {
-        self.error_popup.is_some() || self.help_popup.is_some()
+    pub fn needs_action(&self) -> bool {
+        !self.errors.is_empty() || self.help_popup.is_some()
+    }
+
+    // The same error fired repeatedly (e.g. the daemon going away) is only
+    // queued once
+    pub fn push_error(&mut self, error_popup: ErrorPopup) {
+        if self.errors.back() != Some(&error_popup) {
+            self.errors.push_back(error_popup);
+            self.update_queued_count();
+        }
+    }
+
+    fn update_queued_count(&mut self) {
+        let queued = self.errors.len().saturating_sub(1);
+        if let Some(error_popup) = self.errors.front_mut() {
+            error_popup.set_queued(queued);
+        }
     }
 
     fn toggle_help(&mut self) -> Option<Action> {
@@ -39,22 +58,23 @@ impl GlobalPopupManager {
     }
 
     fn handle_popups(&mut self, action: Action) -> Option<Action> {
-        if let Some(popup) = &mut self.error_popup {
+        if let Some(popup) = self.errors.front_mut() {
             if popup
                 .handle_actions(action)
                 .is_some_and(|a| a.is_soft_quit())
             {
-                self.error_popup = None;
+                self.errors.pop_front();
+                self.update_queued_count();
                 return Some(Action::Render);
             }
         } else if let Some(popup) = &mut self.help_popup {
-            if popup
-                .handle_actions(action)
-                .is_some_and(|a| a.is_soft_quit())
-            {
-                self.help_popup = None;
-                return Some(Action::Render);
-            }
+            return match popup.handle_actions(action) {
+                Some(action) if action.is_soft_quit() => {
+                    self.help_popup = None;
+                    Some(Action::Render)
+                }
+                action => action,
+            };
         }
         None
     }
@@ -71,7 +91,7 @@ impl Component for GlobalPopupManager {
     }
 
     fn render(&mut self, f: &mut Frame, rect: Rect) {
-        if let Some(popup) = &mut self.error_popup {
+        if let Some(popup) = self.errors.front_mut() {
             popup.render(f, rect)
         } else if let Some(popup) = &mut self.help_popup {
             popup.render(f, rect);
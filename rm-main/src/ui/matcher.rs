@@ -0,0 +1,42 @@
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use regex::{Regex, RegexBuilder};
+use rm_config::FilterMode;
+
+// Matches text against what the user typed, according to the configured filter mode.
+pub enum Matcher {
+    // A case-insensitive substring is just an escaped regex
+    Substring(Regex),
+    Fuzzy(Box<SkimMatcherV2>, String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    pub fn new(mode: FilterMode, pattern: &str) -> Result<Self, regex::Error> {
+        match mode {
+            FilterMode::Substring => RegexBuilder::new(&regex::escape(pattern))
+                .case_insensitive(true)
+                .build()
+                .map(Self::Substring),
+            FilterMode::Fuzzy => Ok(Self::Fuzzy(Box::default(), pattern.to_string())),
+            FilterMode::Regex => Regex::new(pattern).map(Self::Regex),
+        }
+    }
+
+    // Returns a score (higher is a better match) and the indices of the matched
+    // characters (not bytes).
+    pub fn matches(&self, text: &str) -> Option<(i64, Vec<usize>)> {
+        match self {
+            Self::Substring(regex) | Self::Regex(regex) => {
+                let found = regex.find(text)?;
+                let indices = text
+                    .char_indices()
+                    .enumerate()
+                    .filter(|(_, (byte_index, _))| found.range().contains(byte_index))
+                    .map(|(index, _)| index)
+                    .collect();
+                Some((0, indices))
+            }
+            Self::Fuzzy(matcher, pattern) => matcher.fuzzy_indices(text, pattern),
+        }
+    }
+}
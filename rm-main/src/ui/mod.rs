@@ -1,11 +1,14 @@
 pub mod components;
 pub mod global_popups;
+pub mod matcher;
+pub mod state;
 pub mod tabs;
 
 use crate::ui::tabs::torrents::TorrentsTab;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::prelude::*;
+use rm_config::keymap::{GeneralAction, Keybinding, TorrentsAction};
 use tui_input::InputRequest;
 
 use crate::{
@@ -28,6 +31,10 @@ pub struct MainWindow {
 }
 
 impl MainWindow {
+    pub const fn current_tab(&self) -> CurrentTab {
+        self.tabs.current_tab
+    }
+
     pub fn new(ctx: app::Ctx) -> Self {
         Self {
             tabs: TabComponent::new(ctx.clone()),
@@ -36,23 +43,35 @@ impl MainWindow {
             global_popup_manager: GlobalPopupManager::new(ctx),
         }
     }
+
+    fn torrents_popup_open(&self) -> bool {
+        self.tabs.current_tab == CurrentTab::Torrents && self.torrents_tab.is_showing_popup()
+    }
 }
 
 impl Component for MainWindow {
     // Rewrite this to one big match
-    #[must_use]
     fn handle_actions(&mut self, action: Action) -> Option<Action> {
         use Action as A;
 
         match action {
             A::Error(e_popup) => {
-                self.global_popup_manager.error_popup = Some(*e_popup);
+                self.global_popup_manager.push_error(*e_popup);
                 Some(A::Render)
             }
             A::ShowHelp => self.global_popup_manager.handle_actions(action),
+            // Wherever the user is, the torrents are now of another server
+            A::ServerSwitched => self.torrents_tab.restart_fetchers(),
+            A::TaskStarted(_) | A::TaskFinished(_) | A::TaskDone(_) | A::TorrentsFetched => {
+                self.torrents_tab.handle_actions(action)
+            }
             _ if self.global_popup_manager.needs_action() => {
                 self.global_popup_manager.handle_actions(action)
             }
+            // An open popup gets Left and Right instead, the tab under it stays
+            A::Left | A::Right if self.torrents_popup_open() => {
+                self.torrents_tab.handle_actions(action)
+            }
             A::ChangeTab(_) | A::Left | A::Right => {
                 self.tabs.handle_actions(action);
                 Some(A::Render)
@@ -82,6 +101,7 @@ impl Component for MainWindow {
     }
 }
 
+// The line editing every text input shares, with the keys of a shell
 const fn to_input_request(key_event: KeyEvent) -> Option<InputRequest> {
     use InputRequest as R;
 
@@ -89,11 +109,87 @@ const fn to_input_request(key_event: KeyEvent) -> Option<InputRequest> {
         (KeyCode::Backspace, KeyModifiers::ALT) => Some(R::DeletePrevWord),
         (KeyCode::Backspace, _) => Some(R::DeletePrevChar),
         (KeyCode::Delete, _) => Some(R::DeleteNextChar),
+        (KeyCode::Left, KeyModifiers::CONTROL) => Some(R::GoToPrevWord),
+        (KeyCode::Right, KeyModifiers::CONTROL) => Some(R::GoToNextWord),
+        (KeyCode::Left, _) => Some(R::GoToPrevChar),
+        (KeyCode::Right, _) => Some(R::GoToNextChar),
+        (KeyCode::Home, _) => Some(R::GoToStart),
+        (KeyCode::End, _) => Some(R::GoToEnd),
+        (KeyCode::Char('w'), KeyModifiers::CONTROL) => Some(R::DeletePrevWord),
+        (KeyCode::Char('u'), KeyModifiers::CONTROL) => Some(R::DeleteLine),
         (KeyCode::Char(char), _) => Some(R::InsertChar(char)),
         _ => None,
     }
 }
 
+// What a paste inserts into a single line input. Line breaks become spaces
+// and other control characters are dropped.
+fn pasted_chars(text: &str) -> impl Iterator<Item = char> + '_ {
+    text.chars()
+        .map(|char| {
+            if char == '\n' || char == '\t' {
+                ' '
+            } else {
+                char
+            }
+        })
+        .filter(|char| !char.is_control())
+}
+
+// "key: desc" pairs of the given actions as bound in the current keymap,
+// meant for the bottom border of a popup. Unbound actions are left out.
+fn keybindings_footer(
+    ctx: &app::Ctx,
+    general: &[GeneralAction],
+    torrents: &[TorrentsAction],
+) -> Line<'static> {
+    fn pairs<T: Copy + PartialEq>(
+        keybindings: &[Keybinding<T>],
+        actions: &[T],
+        desc: fn(T) -> &'static str,
+        arrow_glyphs: bool,
+    ) -> Vec<(String, &'static str)> {
+        actions
+            .iter()
+            .filter_map(|action| {
+                let keys: Vec<_> = keybindings
+                    .iter()
+                    .filter(|keybinding| keybinding.action == *action)
+                    .map(|keybinding| keybinding.keycode_string(arrow_glyphs))
+                    .collect();
+                (!keys.is_empty()).then(|| (keys.join("/"), desc(*action)))
+            })
+            .collect()
+    }
+
+    let keymap = ctx.config.keymap.read().unwrap();
+    let arrow_glyphs = ctx.config.general.arrow_glyphs;
+    let pairs = pairs(
+        &keymap.general.keybindings,
+        general,
+        GeneralAction::desc,
+        arrow_glyphs,
+    )
+    .into_iter()
+    .chain(pairs(
+        &keymap.torrents_tab.keybindings,
+        torrents,
+        TorrentsAction::desc,
+        arrow_glyphs,
+    ));
+
+    let mut spans = vec![];
+    for (keys, desc) in pairs {
+        spans.push(Span::raw(if spans.is_empty() { " " } else { " | " }));
+        spans.push(Span::raw(keys).bold());
+        spans.push(Span::raw(format!(": {desc}")));
+    }
+    if !spans.is_empty() {
+        spans.push(Span::raw(" "));
+    }
+    Line::from(spans)
+}
+
 fn centered_rect(r: Rect, percent_x: u16, percent_y: u16) -> Rect {
     let popup_layout = Layout::vertical([
         Constraint::Percentage((100 - percent_y) / 2),
@@ -0,0 +1,40 @@
+use rm_config::Config;
+use serde::{Deserialize, Serialize};
+
+use crate::ui::tabs::torrents::table_manager::{SortKey, StatusFilter};
+
+// UI preferences restored between runs. Entries missing from an older file
+// keep their defaults.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    pub sort_by: SortKey,
+    pub sort_reverse: bool,
+    pub status_filter: StatusFilter,
+    pub hide_finished: bool,
+    // Of the last active tab, counting from 0
+    pub tab: usize,
+}
+
+impl UiState {
+    pub fn load() -> Self {
+        std::fs::read_to_string(Config::get_state_path())
+            .ok()
+            .and_then(|state| toml::from_str(&state).ok())
+            .unwrap_or_default()
+    }
+
+    // Read again first, so that what the rest of the UI saved is kept
+    pub fn update(change: impl FnOnce(&mut Self)) {
+        let mut state = Self::load();
+        change(&mut state);
+        state.save();
+    }
+
+    // Losing the state isn't worth bothering the user with an error
+    fn save(&self) {
+        if let Ok(state) = toml::to_string(self) {
+            let _ = std::fs::write(Config::get_state_path(), state);
+        }
+    }
+}
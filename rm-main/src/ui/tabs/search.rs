@@ -10,21 +10,22 @@ use ratatui::{
     prelude::*,
     widgets::{Cell, Paragraph, Row, Table},
 };
+use rm_config::Units;
 use throbber_widgets_tui::ThrobberState;
 use tokio::sync::mpsc::{self, UnboundedSender};
-use tui_input::Input;
 
 use crate::{
     action::Action,
     app,
     transmission::TorrentAction,
     ui::{
-        components::{table::GenericTable, Component},
+        components::{input_field::InputField, table::GenericTable, Component},
         to_input_request,
     },
-    utils::bytes_to_human_format,
 };
 
+const SEARCH_PROMPT: &str = " ";
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum SearchFocus {
     Search,
@@ -33,7 +34,7 @@ enum SearchFocus {
 
 pub(crate) struct SearchTab {
     search_focus: SearchFocus,
-    input: Input,
+    input: InputField,
     req_sender: UnboundedSender<String>,
     table: Arc<Mutex<GenericTable<Magnet>>>,
     // TODO: Change it to enum, and combine table with search_result_info, to be behind one mutex
@@ -74,7 +75,7 @@ impl SearchTab {
 
         Self {
             search_focus: SearchFocus::List,
-            input: Input::default(),
+            input: InputField::new(ctx.clone(), SEARCH_PROMPT.to_string()),
             table,
             search_result_info,
             req_sender: tx,
@@ -83,8 +84,8 @@ impl SearchTab {
         }
     }
 
-    fn magnet_to_row(magnet: &Magnet) -> Row {
-        let size = bytes_to_human_format(magnet.bytes as i64);
+    fn magnet_to_row(magnet: &Magnet, units: Units) -> Row<'_> {
+        let size = units.size(magnet.bytes as i64);
         Row::new([
             Cell::from(Cow::Owned(magnet.seeders.to_string())).light_green(),
             Cell::from(Cow::Borrowed(&*magnet.title)),
@@ -111,7 +112,7 @@ impl SearchTab {
             .map(|magnet| magnet.url);
         if let Some(magnet_url) = magnet_url {
             self.ctx
-                .send_torrent_action(TorrentAction::Add(magnet_url, None));
+                .send_torrent_action(TorrentAction::Add(magnet_url, None, false));
         }
         None
     }
@@ -121,7 +122,7 @@ impl SearchTab {
 
         match input.code {
             KeyCode::Enter => {
-                self.req_sender.send(self.input.to_string()).unwrap();
+                self.req_sender.send(self.input.text()).unwrap();
                 self.search_focus = SearchFocus::List;
                 Some(A::SwitchToNormalMode)
             }
@@ -146,12 +147,12 @@ impl SearchTab {
     }
 
     fn next_torrent(&mut self) -> Option<Action> {
-        self.table.lock().unwrap().next();
+        self.table.lock().unwrap().next(true);
         Some(Action::Render)
     }
 
     fn previous_torrent(&mut self) -> Option<Action> {
-        self.table.lock().unwrap().previous();
+        self.table.lock().unwrap().previous(true);
         Some(Action::Render)
     }
 
@@ -159,7 +160,7 @@ impl SearchTab {
         self.table
             .lock()
             .unwrap()
-            .scroll_down_by(self.currently_displaying_no as usize);
+            .scroll_down_by(self.currently_displaying_no as usize, false);
         Some(Action::Render)
     }
 
@@ -167,7 +168,7 @@ impl SearchTab {
         self.table
             .lock()
             .unwrap()
-            .scroll_up_by(self.currently_displaying_no as usize);
+            .scroll_up_by(self.currently_displaying_no as usize, false);
         Some(Action::Render)
     }
 
@@ -190,6 +191,10 @@ impl Component for SearchTab {
             A::Search => self.start_search(),
             A::ChangeFocus => self.change_focus(),
             A::Input(input) => self.handle_input(input),
+            A::Paste(text) => {
+                self.input.paste(&text);
+                Some(A::Render)
+            }
             A::Down => self.next_torrent(),
             A::Up => self.previous_torrent(),
             A::ScrollDownPage => self.scroll_down_page(),
@@ -226,14 +231,6 @@ impl Component for SearchTab {
         .flex(Flex::Center)
         .split(top_line)[1];
 
-        let input = {
-            if self.input.value().is_empty() && self.search_focus != SearchFocus::Search {
-                "press / to search"
-            } else {
-                self.input.value()
-            }
-        };
-
         let search_style = {
             if self.search_focus == SearchFocus::Search {
                 Style::default()
@@ -244,17 +241,15 @@ impl Component for SearchTab {
             }
         };
 
-        let paragraph_text = format!(" {input}");
-        let prefix_len = paragraph_text.len() - input.len() - 2;
-        let paragraph = Paragraph::new(paragraph_text).style(search_style);
-
-        f.render_widget(paragraph, search_rect);
-
-        let cursor_offset = self.input.visual_cursor() + prefix_len;
-        f.set_cursor(
-            search_rect.x + u16::try_from(cursor_offset).unwrap(),
-            search_rect.y,
-        );
+        if self.input.is_empty() && self.search_focus != SearchFocus::Search {
+            let placeholder = Paragraph::new(format!("{SEARCH_PROMPT}press / to search"));
+            f.render_widget(placeholder.style(search_style), search_rect);
+        } else {
+            self.input.set_style(search_style);
+            self.input
+                .set_focused(self.search_focus == SearchFocus::Search);
+            self.input.render(f, search_rect);
+        }
 
         let header = Row::new(["S", "Title", "Size"]);
 
@@ -262,7 +257,10 @@ impl Component for SearchTab {
         let table_items = &table_lock.items;
 
         let longest_title = table_items.iter().map(|magnet| magnet.title.len()).max();
-        let items = table_items.iter().map(Self::magnet_to_row);
+        let units = self.ctx.config.units();
+        let items = table_items
+            .iter()
+            .map(|magnet| Self::magnet_to_row(magnet, units));
 
         let widths = [
             Constraint::Length(5),                                  // Seeders
@@ -323,7 +321,7 @@ impl SearchResultState {
 impl Component for SearchResultState {
     fn render(&mut self, f: &mut Frame, rect: Rect) {
         match self {
-            SearchResultState::Nothing => return,
+            SearchResultState::Nothing => (),
             SearchResultState::Searching(state) => {
                 let default_throbber = throbber_widgets_tui::Throbber::default()
                     .label("Searching...")
@@ -1,24 +1,89 @@
 use std::{
     borrow::Borrow,
+    collections::VecDeque,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use ratatui::{
     layout::{Alignment, Rect},
-    widgets::Paragraph,
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Paragraph, Sparkline},
     Frame,
 };
+use rm_config::{Config, SpeedGraph, Units};
 use transmission_rpc::types::{FreeSpace, SessionStats};
 
-use crate::{ui::components::Component, utils::bytes_to_human_format};
+use crate::{
+    action::Action, app::Connection, ui::components::Component, utils::seconds_to_human_format,
+};
+
+use super::table_manager::{StatusFilter, TableManager};
+
+// Space kept for the task bar, which shares the line with the stats
+const MIN_TASK_WIDTH: u16 = 30;
+// Any narrower and the graph isn't worth showing
+const MIN_GRAPH_WIDTH: u16 = 5;
+
+// Recent aggregate speeds, oldest first.
+pub struct SpeedHistory {
+    samples: VecDeque<(i64, i64)>,
+    length: usize,
+}
+
+impl SpeedHistory {
+    pub fn new(length: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(length),
+            length,
+        }
+    }
 
-use super::table_manager::TableManager;
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn push(&mut self, download_speed: i64, upload_speed: i64) {
+        if self.length == 0 {
+            return;
+        }
+        if self.samples.len() == self.length {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((download_speed, upload_speed));
+    }
+
+    fn downloads(&self) -> Vec<u64> {
+        self.samples
+            .iter()
+            .map(|(download, _)| (*download).try_into().unwrap_or_default())
+            .collect()
+    }
+
+    fn uploads(&self) -> Vec<u64> {
+        self.samples
+            .iter()
+            .map(|(_, upload)| (*upload).try_into().unwrap_or_default())
+            .collect()
+    }
+}
 
 pub(super) struct BottomStats {
     // TODO: get rid of the Option
     pub(super) stats: Arc<Mutex<Option<SessionStats>>>,
+    pub(super) speed_history: Arc<Mutex<SpeedHistory>>,
+    speed_graph: SpeedGraph,
     pub(super) free_space: Arc<Mutex<Option<FreeSpace>>>,
+    // Read with every stats fetch, and flipped right away when toggled here
+    pub(super) alt_speed_enabled: Arc<Mutex<bool>>,
     pub(super) table_manager: Arc<Mutex<TableManager>>,
+    connection: Arc<Connection>,
+    units: Units,
+    // Torrents older than that are shown as stale
+    stale_after: Duration,
+    // What was last drawn, so that ticks only redraw when it changes
+    last_freshness: Option<Span<'static>>,
 }
 
 impl BottomStats {
@@ -26,28 +91,76 @@ impl BottomStats {
         stats: Arc<Mutex<Option<SessionStats>>>,
         free_space: Arc<Mutex<Option<FreeSpace>>>,
         table_manager: Arc<Mutex<TableManager>>,
+        connection: Arc<Connection>,
+        config: &Config,
     ) -> Self {
+        let speed_graph_length = config.general.speed_graph_length;
         Self {
             stats,
+            speed_history: Arc::new(Mutex::new(SpeedHistory::new(speed_graph_length))),
+            speed_graph: config.general.speed_graph,
             free_space,
+            alt_speed_enabled: Arc::new(Mutex::new(false)),
             table_manager,
+            connection,
+            units: config.units(),
+            stale_after: config.torrents_refresh() * 3,
+            last_freshness: None,
         }
     }
+
+    // Connection state and how long ago the torrents were fetched
+    fn freshness(&self) -> Option<Span<'static>> {
+        let since_update = self.connection.since_update()?;
+        let ago = format!(
+            "updated {} ago",
+            seconds_to_human_format(since_update.as_secs().try_into().unwrap_or(i64::MAX))
+        );
+
+        let span = if self.connection.is_lost() {
+            Span::raw(format!("Reconnecting… ({ago})")).red()
+        } else if since_update > self.stale_after {
+            Span::raw(ago).yellow()
+        } else {
+            Span::raw(ago).dark_gray()
+        };
+        Some(span)
+    }
 }
 impl Component for BottomStats {
+    fn handle_actions(&mut self, action: Action) -> Option<Action> {
+        match action {
+            Action::Tick if self.freshness() != self.last_freshness => Some(Action::Render),
+            _ => None,
+        }
+    }
+
     fn render(&mut self, f: &mut Frame, rect: Rect) {
         if let Some(stats) = &*self.stats.lock().unwrap() {
-            let download = bytes_to_human_format(stats.download_speed);
-            let upload = bytes_to_human_format(stats.upload_speed);
+            let download = self.units.speed(stats.download_speed);
+            let upload = self.units.speed(stats.upload_speed);
 
             let mut text = format!("▼ {download} | ▲ {upload}");
 
+            if *self.alt_speed_enabled.lock().unwrap() {
+                text = format!("🐢 | {text}");
+            }
+
             if let Some(free_space) = &*self.free_space.lock().unwrap() {
-                let free_space = bytes_to_human_format(free_space.size_bytes);
+                let free_space = self.units.size(free_space.size_bytes);
                 text = format!("󰋊 {free_space} | {text}")
             }
 
             let table_manager = &*self.table_manager.lock().unwrap();
+
+            if table_manager.status_filter != StatusFilter::All {
+                text = format!("Showing: {} | {text}", table_manager.status_filter.name());
+            }
+
+            if table_manager.hide_finished {
+                text = format!("Finished hidden | {text}");
+            }
+
             let table = table_manager.table.borrow();
             let all = table.get_len();
 
@@ -63,8 +176,62 @@ impl Component for BottomStats {
                 text = format!(" {all} | {text}");
             }
 
-            let paragraph = Paragraph::new(text).alignment(Alignment::Right);
+            if let Some(server_name) = self.connection.server_name() {
+                text = format!("{server_name} | {text}");
+            }
+
+            let freshness = self.freshness();
+            let mut spans = vec![];
+            if let Some(freshness) = &freshness {
+                spans.push(freshness.clone());
+                spans.push(Span::raw(" | "));
+            }
+            spans.push(Span::raw(text));
+            self.last_freshness = freshness;
+
+            let line = Line::from(spans);
+            let text_width = u16::try_from(line.width()).unwrap_or(u16::MAX);
+            let paragraph = Paragraph::new(line).alignment(Alignment::Right);
             f.render_widget(paragraph, rect);
+
+            self.render_speed_graph(f, rect, text_width);
+        }
+    }
+}
+
+impl BottomStats {
+    // Draws the graphs right before the stats text.
+    fn render_speed_graph(&self, f: &mut Frame, rect: Rect, text_width: u16) {
+        let speed_history = self.speed_history.lock().unwrap();
+        let graphs = match self.speed_graph {
+            SpeedGraph::Off => return,
+            SpeedGraph::Download => vec![(speed_history.downloads(), Color::Green)],
+            SpeedGraph::Upload => vec![(speed_history.uploads(), Color::Blue)],
+            SpeedGraph::Both => vec![
+                (speed_history.downloads(), Color::Green),
+                (speed_history.uploads(), Color::Blue),
+            ],
+        };
+
+        let graphs_no = u16::try_from(graphs.len()).unwrap_or(1);
+        let available_width = rect.width.saturating_sub(text_width + MIN_TASK_WIDTH) / graphs_no;
+        // A column on the right of each graph separates it from what follows
+        let graph_width = u16::try_from(speed_history.length)
+            .unwrap_or(u16::MAX)
+            .min(available_width.saturating_sub(1));
+        if graph_width < MIN_GRAPH_WIDTH {
+            return;
+        }
+
+        let mut x = rect.right() - text_width;
+        for (data, color) in graphs.iter().rev() {
+            x -= graph_width + 1;
+            let graph_rect = Rect::new(x, rect.y, graph_width, 1);
+            let newest = &data[data.len().saturating_sub(graph_width.into())..];
+            let sparkline = Sparkline::default()
+                .data(newest)
+                .style(Style::default().fg(*color));
+            f.render_widget(sparkline, graph_rect);
         }
     }
 }
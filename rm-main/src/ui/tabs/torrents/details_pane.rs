@@ -0,0 +1,68 @@
+use std::sync::{Arc, Mutex};
+
+use ratatui::{
+    prelude::*,
+    widgets::{block::Title, Block, BorderType, Paragraph, Wrap},
+};
+use transmission_rpc::types::{Id, Torrent};
+
+use crate::{app, transmission::TorrentAction};
+
+use super::popups::info::InfoPopup;
+
+// Info of the highlighted torrent, shown next to the table.
+pub struct DetailsPane {
+    ctx: app::Ctx,
+    torrent_id: Option<Id>,
+    torrent: Arc<Mutex<Option<Torrent>>>,
+}
+
+impl DetailsPane {
+    pub fn new(ctx: app::Ctx) -> Self {
+        Self {
+            ctx,
+            torrent_id: None,
+            torrent: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    // Fetches the info whenever the highlighted torrent changes, and again on
+    // refetch. The old info of the same torrent stays until the new one is in.
+    pub fn update(&mut self, torrent_id: Option<Id>, refetch: bool) {
+        if self.torrent_id != torrent_id {
+            self.torrent = Arc::new(Mutex::new(None));
+            self.torrent_id = torrent_id;
+        } else if !refetch {
+            return;
+        }
+
+        if let Some(torrent_id) = &self.torrent_id {
+            self.ctx.send_torrent_action(TorrentAction::GetTorrentInfo(
+                torrent_id.clone(),
+                Arc::clone(&self.torrent),
+            ));
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, rect: Rect) {
+        let title_style = Style::default().fg(self.ctx.config.general.accent_color);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Title::from(" Details ".set_style(title_style)));
+
+        let paragraph = match (&*self.torrent.lock().unwrap(), &self.torrent_id) {
+            (Some(torrent), _) => {
+                let lines = InfoPopup::torrent_to_lines(
+                    torrent,
+                    self.ctx.config.units(),
+                    &self.ctx.config.general.datetime_format,
+                );
+                Paragraph::new(lines).wrap(Wrap { trim: false })
+            }
+            (None, Some(_)) => Paragraph::new("Loading..."),
+            (None, None) => Paragraph::new("No torrent highlighted").dark_gray(),
+        };
+
+        f.render_widget(paragraph.block(block), rect);
+    }
+}
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use rm_config::{Config, ExportFormat};
+use serde::Serialize;
+
+use super::rustmission_torrent::RustmissionTorrent;
+
+// Raw values rather than what the table shows, as it's meant for scripts
+#[derive(Serialize)]
+struct ExportedTorrent<'a> {
+    name: &'a str,
+    status: &'static str,
+    size_bytes: i64,
+    progress_percent: f32,
+    // Transmission's "not available" and "infinite" are left out
+    ratio: Option<f32>,
+}
+
+impl<'a> From<&'a RustmissionTorrent> for ExportedTorrent<'a> {
+    fn from(torrent: &'a RustmissionTorrent) -> Self {
+        Self {
+            name: &torrent.torrent_name,
+            status: torrent.status_text(),
+            size_bytes: torrent.size_when_done_bytes,
+            progress_percent: torrent.percent_done * 100f32,
+            ratio: Some(torrent.upload_ratio).filter(|ratio| *ratio >= 0f32),
+        }
+    }
+}
+
+pub fn export(torrents: &[&RustmissionTorrent], format: ExportFormat) -> Result<PathBuf> {
+    let torrents: Vec<_> = torrents
+        .iter()
+        .map(|torrent| ExportedTorrent::from(*torrent))
+        .collect();
+
+    let contents = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&torrents)?,
+        ExportFormat::Csv => to_csv(&torrents),
+    };
+
+    let path = Config::get_export_path(format)?;
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+fn to_csv(torrents: &[ExportedTorrent]) -> String {
+    // Quoted only when needed, with quotes doubled as per RFC 4180
+    fn field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    let mut csv = String::from("name,status,size_bytes,progress_percent,ratio\n");
+    for torrent in torrents {
+        let ratio = torrent
+            .ratio
+            .map(|ratio| ratio.to_string())
+            .unwrap_or_default();
+        csv.push_str(&format!(
+            "{},{},{},{},{ratio}\n",
+            field(torrent.name),
+            torrent.status,
+            torrent.size_bytes,
+            torrent.progress_percent,
+        ));
+    }
+    csv
+}
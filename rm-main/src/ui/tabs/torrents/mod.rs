@@ -1,37 +1,63 @@
-mod bottom_stats;
-mod input_manager;
+pub mod bottom_stats;
+mod details_pane;
+mod export;
 pub mod popups;
 pub mod rustmission_torrent;
 pub mod table_manager;
 pub mod task_manager;
 pub mod tasks;
 
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
 use std::sync::{Arc, Mutex};
 
 use crate::transmission::TorrentAction;
 use crate::ui::tabs::torrents::popups::stats::StatisticsPopup;
 
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::prelude::*;
 use ratatui::widgets::{Row, Table};
-use transmission_rpc::types::TorrentStatus;
+use rm_config::ConfirmAction;
+use tokio::task::JoinHandle;
+use transmission_rpc::types::{TorrentSetArgs, TorrentStatus};
 
 use crate::action::Action;
 use crate::ui::components::table::GenericTable;
 use crate::ui::components::Component;
+use crate::ui::global_popups::ErrorPopup;
 use crate::{app, transmission};
 
 use self::bottom_stats::BottomStats;
+use self::details_pane::DetailsPane;
+use self::popups::confirmation::ConfirmationPopup;
 use self::popups::files::FilesPopup;
+use self::popups::info::InfoPopup;
+use self::popups::rename::RenamePopup;
+use self::popups::seed_limits::SeedLimitsPopup;
+use self::popups::speed_limit::SpeedLimitPopup;
+use self::popups::trackers::TrackersPopup;
 use self::popups::{CurrentPopup, PopupManager};
 use self::table_manager::TableManager;
 use self::task_manager::TaskManager;
 
+#[derive(Clone, Copy)]
+enum QueueMove {
+    Up,
+    Down,
+    Top,
+    Bottom,
+}
+
 pub struct TorrentsTab {
     ctx: app::Ctx,
     table_manager: Arc<Mutex<TableManager>>,
     popup_manager: PopupManager,
     task_manager: TaskManager,
     bottom_stats: BottomStats,
+    details_pane: Option<DetailsPane>,
+    // Where the table was last drawn, for mapping clicks to rows
+    table_rect: Rect,
+    fetchers: Vec<JoinHandle<()>>,
 }
 
 impl TorrentsTab {
@@ -40,25 +66,27 @@ impl TorrentsTab {
         let table_manager = Arc::new(Mutex::new(TableManager::new(ctx.clone(), table)));
         let stats = Arc::new(Mutex::new(None));
         let free_space = Arc::new(Mutex::new(None));
-        let bottom_stats = BottomStats::new(stats, free_space, Arc::clone(&table_manager));
-
-        tokio::spawn(transmission::fetchers::stats(
-            ctx.clone(),
-            Arc::clone(&bottom_stats.stats),
-        ));
+        let bottom_stats = BottomStats::new(
+            stats,
+            free_space,
+            Arc::clone(&table_manager),
+            Arc::clone(&ctx.connection),
+            &ctx.config,
+        );
 
-        tokio::spawn(transmission::fetchers::torrents(
-            ctx.clone(),
-            Arc::clone(&bottom_stats.table_manager),
-        ));
+        let fetchers = Self::spawn_fetchers(&ctx, &bottom_stats);
 
-        tokio::spawn(transmission::fetchers::free_space(
-            ctx.clone(),
-            Arc::clone(&bottom_stats.free_space),
-        ));
+        let details_pane = ctx
+            .config
+            .torrents_tab
+            .details_pane
+            .then(|| DetailsPane::new(ctx.clone()));
 
         Self {
             bottom_stats,
+            details_pane,
+            table_rect: Rect::default(),
+            fetchers,
             task_manager: TaskManager::new(table_manager.clone(), ctx.clone()),
             table_manager,
             popup_manager: PopupManager::new(),
@@ -72,7 +100,18 @@ impl Component for TorrentsTab {
         let [torrents_list_rect, stats_rect] =
             Layout::vertical([Constraint::Min(10), Constraint::Length(1)]).areas(rect);
 
-        self.render_table(f, torrents_list_rect);
+        if self.details_pane.is_some() {
+            let [table_rect, details_rect] =
+                Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .areas(torrents_list_rect);
+            self.render_table(f, table_rect);
+
+            if let Some(details_pane) = &mut self.details_pane {
+                details_pane.render(f, details_rect);
+            }
+        } else {
+            self.render_table(f, torrents_list_rect);
+        }
 
         self.bottom_stats.render(f, stats_rect);
 
@@ -81,9 +120,31 @@ impl Component for TorrentsTab {
         self.popup_manager.render(f, f.size());
     }
 
-    #[must_use]
     fn handle_actions(&mut self, action: Action) -> Option<Action> {
+        let refetch = action == Action::TorrentsFetched;
+        let action = if refetch {
+            Some(Action::Render)
+        } else {
+            self.dispatch(action)
+        };
+        self.update_details_pane(refetch);
+        action
+    }
+}
+
+impl TorrentsTab {
+    fn dispatch(&mut self, action: Action) -> Option<Action> {
         use Action as A;
+        // The innermost overlay goes first: a popup closes on either Quit or
+        // SoftQuit, then SoftQuit dismisses a status message. Only a Quit
+        // with nothing open exits, SoftQuit never does.
+        if matches!(
+            action,
+            A::TaskStarted(_) | A::TaskFinished(_) | A::TaskDone(_)
+        ) {
+            // Shown even with a popup on top
+            return self.task_manager.track_progress(&action);
+        }
         if self.popup_manager.is_showing_popup() {
             return self.popup_manager.handle_actions(action);
         }
@@ -95,38 +156,152 @@ impl Component for TorrentsTab {
         match action {
             A::Up => self.previous_torrent(),
             A::Down => self.next_torrent(),
+            A::SelectUp => self.extend_selection(false),
+            A::SelectDown => self.extend_selection(true),
             A::ScrollUpPage => self.scroll_page_up(),
             A::ScrollDownPage => self.scroll_page_down(),
             A::Home => self.scroll_to_home(),
             A::End => self.scroll_to_end(),
             A::ShowStats => self.show_statistics_popup(),
             A::ShowFiles => self.show_files_popup(),
+            A::ShowInfo => self.show_info_popup(),
+            A::ToggleDetails => self.toggle_details_pane(),
+            A::ToggleRowHeight => self.toggle_row_height(),
+            A::SpeedLimit => self.show_speed_limit_popup(),
+            A::GlobalSpeedLimit => self.show_global_speed_limit_popup(),
+            A::SeedLimits => self.show_seed_limits_popup(),
+            A::EditTrackers => self.show_trackers_popup(),
+            A::Rename => self.show_rename_popup(),
             A::Pause => self.pause_current_torrent(),
+            A::StartNow => self.start_now(),
+            A::StartAll => self.start_all_torrents(),
+            A::StopAll => self.stop_all_torrents(),
+            A::Space => self.toggle_selection(),
+            A::Verify => self.verify_torrents(),
+            A::DeleteWithFiles => self.show_delete_with_files_popup(),
+            A::UndoDelete => self.undo_delete(),
+            A::SortBy => self.cycle_sort_key(),
+            A::ToggleSortOrder => self.toggle_sort_order(),
+            A::CycleStatusFilter => self.cycle_status_filter(),
+            A::ToggleFinished => self.toggle_hide_finished(),
+            A::ToggleAltSpeed => self.toggle_alt_speed(),
+            A::QueueUp => self.move_in_queue(QueueMove::Up),
+            A::QueueDown => self.move_in_queue(QueueMove::Down),
+            A::QueueTop => self.move_in_queue(QueueMove::Top),
+            A::QueueBottom => self.move_in_queue(QueueMove::Bottom),
+            A::OpenDownloadDir => self.open_download_dir(),
+            A::Export => self.export_torrents(),
+            A::Mouse(mouse) => self.handle_mouse(mouse),
+            A::Tick => {
+                let task_action = self.task_manager.handle_actions(A::Tick);
+                self.bottom_stats.handle_actions(A::Tick).or(task_action)
+            }
             other => self.task_manager.handle_actions(other),
         }
     }
 }
 
 impl TorrentsTab {
+    fn spawn_fetchers(ctx: &app::Ctx, bottom_stats: &BottomStats) -> Vec<JoinHandle<()>> {
+        vec![
+            tokio::spawn(transmission::fetchers::stats(
+                ctx.clone(),
+                Arc::clone(&bottom_stats.stats),
+                Arc::clone(&bottom_stats.speed_history),
+                Arc::clone(&bottom_stats.alt_speed_enabled),
+                ctx.config.stats_refresh(),
+            )),
+            tokio::spawn(transmission::fetchers::torrents(
+                ctx.clone(),
+                Arc::clone(&bottom_stats.table_manager),
+                ctx.config.torrents_refresh(),
+            )),
+            tokio::spawn(transmission::fetchers::free_space(
+                ctx.clone(),
+                Arc::clone(&bottom_stats.free_space),
+            )),
+            tokio::spawn(transmission::fetchers::auto_remove(ctx.clone())),
+        ]
+    }
+
+    pub const fn is_showing_popup(&self) -> bool {
+        self.popup_manager.is_showing_popup()
+    }
+
+    // Nothing fetched from the previous server should be left behind
+    pub fn restart_fetchers(&mut self) -> Option<Action> {
+        for fetcher in self.fetchers.drain(..) {
+            fetcher.abort();
+        }
+
+        self.table_manager.lock().unwrap().set_new_rows(vec![]);
+        if self.details_pane.is_some() {
+            self.details_pane = Some(DetailsPane::new(self.ctx.clone()));
+        }
+        *self.bottom_stats.stats.lock().unwrap() = None;
+        *self.bottom_stats.free_space.lock().unwrap() = None;
+        self.bottom_stats.speed_history.lock().unwrap().clear();
+        *self.bottom_stats.alt_speed_enabled.lock().unwrap() = false;
+
+        self.fetchers = Self::spawn_fetchers(&self.ctx, &self.bottom_stats);
+        Some(Action::Render)
+    }
+
     fn render_table(&mut self, f: &mut Frame, rect: Rect) {
         let table_manager_lock = &mut *self.table_manager.lock().unwrap();
-        table_manager_lock.torrents_displaying_no = rect.height;
+        table_manager_lock.lay_out_columns(rect);
+
+        let full_name = if self.ctx.config.torrents_tab.show_full_name {
+            table_manager_lock.full_name_footer()
+        } else {
+            None
+        };
+        let rect = if let Some(full_name) = full_name {
+            let [table_rect, footer_rect] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(rect);
+            f.render_widget(Line::from(full_name).dark_gray(), footer_rect);
+            table_rect
+        } else {
+            rect
+        };
+        // Clicks on the totals don't select anything
+        let show_totals = self.ctx.config.torrents_tab.show_totals;
+        let footer_height = u16::from(show_totals);
+        self.table_rect = Rect {
+            height: rect.height.saturating_sub(footer_height),
+            ..rect
+        };
+
+        // A page is as many rows as fit between the header and the totals
+        let header_height = u16::from(!self.ctx.config.general.headers_hide);
+        table_manager_lock.torrents_displaying_no =
+            (rect.height.saturating_sub(header_height + footer_height)
+                / table_manager_lock.row_height())
+            .max(1);
 
         let torrent_rows = table_manager_lock.rows();
 
-        let highlight_table_style = Style::default().on_black().bold().fg(self
-            .ctx
-            .config
-            .general
-            .accent_color);
+        let theme = &self.ctx.config.theme;
+        let highlight_table_style = theme.selected_row.to_style().fg(theme
+            .selected_row
+            .fg
+            .unwrap_or(self.ctx.config.general.accent_color));
 
         let table_widget = {
-            let table = Table::new(torrent_rows, table_manager_lock.widths)
-                .highlight_style(highlight_table_style);
+            let widths = table_manager_lock
+                .column_widths
+                .iter()
+                .map(|width| Constraint::Length(*width));
+            let mut table = Table::new(torrent_rows, widths).highlight_style(highlight_table_style);
+            if show_totals {
+                table = table.footer(
+                    table_manager_lock
+                        .totals_row()
+                        .style(theme.header.to_style()),
+                );
+            }
             if !self.ctx.config.general.headers_hide {
-                table.header(Row::new(
-                    table_manager_lock.header().iter().map(|s| s.as_str()),
-                ))
+                table.header(Row::new(table_manager_lock.header()).style(theme.header.to_style()))
             } else {
                 table
             }
@@ -149,6 +324,153 @@ impl TorrentsTab {
         }
     }
 
+    fn show_info_popup(&mut self) -> Option<Action> {
+        if let Some(highlighted_torrent) = self.table_manager.lock().unwrap().current_torrent() {
+            let popup = InfoPopup::new(self.ctx.clone(), highlighted_torrent.id.clone());
+            self.popup_manager.show_popup(CurrentPopup::Info(popup));
+            Some(Action::Render)
+        } else {
+            None
+        }
+    }
+
+    // Keeps the pane on the highlighted torrent, and its info as fresh as the
+    // table's when refetch is set after a torrents fetch
+    fn update_details_pane(&mut self, refetch: bool) {
+        let Some(details_pane) = &mut self.details_pane else {
+            return;
+        };
+        let torrent_id = self
+            .table_manager
+            .lock()
+            .unwrap()
+            .current_torrent()
+            .map(|torrent| torrent.id.clone());
+        details_pane.update(torrent_id, refetch);
+    }
+
+    fn toggle_details_pane(&mut self) -> Option<Action> {
+        self.details_pane = match self.details_pane {
+            Some(_) => None,
+            None => Some(DetailsPane::new(self.ctx.clone())),
+        };
+        Some(Action::Render)
+    }
+
+    fn toggle_row_height(&self) -> Option<Action> {
+        let mut table_manager = self.table_manager.lock().unwrap();
+        table_manager.expanded_rows = !table_manager.expanded_rows;
+        Some(Action::Render)
+    }
+
+    fn show_speed_limit_popup(&mut self) -> Option<Action> {
+        let torrent_ids = self.table_manager.lock().unwrap().selected_or_current_ids();
+        if torrent_ids.is_empty() {
+            return None;
+        }
+
+        let popup = SpeedLimitPopup::new(self.ctx.clone(), torrent_ids);
+        self.popup_manager
+            .show_popup(CurrentPopup::SpeedLimit(popup));
+        Some(Action::SwitchToInputMode)
+    }
+
+    fn show_global_speed_limit_popup(&mut self) -> Option<Action> {
+        let popup = SpeedLimitPopup::new_global(self.ctx.clone());
+        self.popup_manager
+            .show_popup(CurrentPopup::SpeedLimit(popup));
+        Some(Action::SwitchToInputMode)
+    }
+
+    fn export_torrents(&mut self) -> Option<Action> {
+        let format = self.ctx.config.torrents_tab.export_format;
+        let table_manager = self.table_manager.lock().unwrap();
+        let torrents = table_manager.visible_rows();
+        match export::export(&torrents, format) {
+            Ok(path) => Some(Action::ShowStatus(format!(
+                "Exported {} torrents to {}",
+                torrents.len(),
+                path.display()
+            ))),
+            Err(e) => {
+                let error_popup = ErrorPopup::new("Failed to export the torrents", e.to_string());
+                Some(Action::Error(Box::new(error_popup)))
+            }
+        }
+    }
+
+    fn show_seed_limits_popup(&mut self) -> Option<Action> {
+        let torrent_ids = self.table_manager.lock().unwrap().selected_or_current_ids();
+        if torrent_ids.is_empty() {
+            return None;
+        }
+
+        let popup = SeedLimitsPopup::new(self.ctx.clone(), torrent_ids);
+        self.popup_manager
+            .show_popup(CurrentPopup::SeedLimits(popup));
+        Some(Action::SwitchToInputMode)
+    }
+
+    fn show_trackers_popup(&mut self) -> Option<Action> {
+        let torrent_id = self
+            .table_manager
+            .lock()
+            .unwrap()
+            .current_torrent()?
+            .id
+            .clone();
+        let popup = TrackersPopup::new(self.ctx.clone(), torrent_id);
+        self.popup_manager.show_popup(CurrentPopup::Trackers(popup));
+        Some(Action::SwitchToInputMode)
+    }
+
+    fn show_rename_popup(&mut self) -> Option<Action> {
+        let mut table_manager = self.table_manager.lock().unwrap();
+        let torrent = table_manager.current_torrent()?;
+        let popup = RenamePopup::new(
+            self.ctx.clone(),
+            torrent.id.clone(),
+            torrent.torrent_name.clone(),
+        );
+        drop(table_manager);
+        self.popup_manager.show_popup(CurrentPopup::Rename(popup));
+        Some(Action::SwitchToInputMode)
+    }
+
+    fn show_delete_with_files_popup(&mut self) -> Option<Action> {
+        let mut table_manager = self.table_manager.lock().unwrap();
+        let torrents = table_manager.selected_or_current_torrents();
+
+        let message = match torrents.as_slice() {
+            [] => return None,
+            [torrent] => format!(
+                "Delete \"{}\" and all its files? (y/n)",
+                torrent.torrent_name
+            ),
+            torrents => format!(
+                "Delete {} torrents and all their files? (y/n)",
+                torrents.len()
+            ),
+        };
+        let torrent_ids = torrents.iter().map(|torrent| torrent.id.clone()).collect();
+
+        if !self.ctx.config.confirms(ConfirmAction::DeleteWithFiles) {
+            self.ctx
+                .send_torrent_action(TorrentAction::DeleteWithFiles(torrent_ids));
+            return None;
+        }
+
+        let popup = ConfirmationPopup::new(
+            self.ctx.clone(),
+            "Delete with files".to_string(),
+            message,
+            TorrentAction::DeleteWithFiles(torrent_ids),
+        );
+        self.popup_manager
+            .show_popup(CurrentPopup::Confirmation(popup));
+        Some(Action::SwitchToInputMode)
+    }
+
     fn show_statistics_popup(&mut self) -> Option<Action> {
         if let Some(stats) = &*self.bottom_stats.stats.lock().unwrap() {
             let popup = StatisticsPopup::new(self.ctx.clone(), stats.clone());
@@ -160,59 +482,319 @@ impl TorrentsTab {
     }
 
     fn previous_torrent(&self) -> Option<Action> {
-        self.table_manager.lock().unwrap().table.previous();
+        let wrap = self.ctx.config.torrents_tab.wrap_selection;
+        let mut table_manager = self.table_manager.lock().unwrap();
+        table_manager.collapse_range();
+        table_manager.table.previous(wrap);
         Some(Action::Render)
     }
 
     fn next_torrent(&self) -> Option<Action> {
-        self.table_manager.lock().unwrap().table.next();
+        let wrap = self.ctx.config.torrents_tab.wrap_selection;
+        let mut table_manager = self.table_manager.lock().unwrap();
+        table_manager.collapse_range();
+        table_manager.table.next(wrap);
+        Some(Action::Render)
+    }
+
+    fn extend_selection(&self, down: bool) -> Option<Action> {
+        self.table_manager.lock().unwrap().extend_selection(down);
+        Some(Action::Render)
+    }
+
+    fn handle_mouse(&self, mouse: MouseEvent) -> Option<Action> {
+        let table_manager = &mut self.table_manager.lock().unwrap();
+        match mouse.kind {
+            MouseEventKind::ScrollDown => table_manager.table.scroll_down_by(1, false),
+            MouseEventKind::ScrollUp => table_manager.table.scroll_up_by(1, false),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let rect = self.table_rect;
+                let header_height = u16::from(!self.ctx.config.general.headers_hide);
+                let rows_top = rect.y + header_height;
+                if mouse.column < rect.x
+                    || mouse.column >= rect.right()
+                    || mouse.row < rows_top
+                    || mouse.row >= rect.bottom()
+                {
+                    return None;
+                }
+
+                let table = &table_manager.table;
+                let offset = table.state.borrow().offset();
+                let row_height = table_manager.row_height();
+                let index = offset + usize::from((mouse.row - rows_top) / row_height);
+                if index >= table.get_len() {
+                    return None;
+                }
+                table.state.borrow_mut().select(Some(index));
+                table_manager.collapse_range();
+            }
+            _ => return None,
+        }
         Some(Action::Render)
     }
 
     fn scroll_page_down(&self) -> Option<Action> {
         let table_manager = &mut self.table_manager.lock().unwrap();
         let scroll_by = table_manager.torrents_displaying_no;
-        table_manager.table.scroll_down_by(scroll_by as usize);
+        let wrap = self.ctx.config.torrents_tab.wrap_selection;
+        table_manager.collapse_range();
+        table_manager.table.scroll_down_by(scroll_by as usize, wrap);
         Some(Action::Render)
     }
 
     fn scroll_page_up(&self) -> Option<Action> {
         let table_manager = &mut self.table_manager.lock().unwrap();
         let scroll_by = table_manager.torrents_displaying_no;
-        table_manager.table.scroll_up_by(scroll_by as usize);
+        let wrap = self.ctx.config.torrents_tab.wrap_selection;
+        table_manager.collapse_range();
+        table_manager.table.scroll_up_by(scroll_by as usize, wrap);
         Some(Action::Render)
     }
 
     fn scroll_to_home(&self) -> Option<Action> {
         let table_manager = &mut self.table_manager.lock().unwrap();
+        table_manager.collapse_range();
         table_manager.table.scroll_to_home();
         Some(Action::Render)
     }
 
     fn scroll_to_end(&self) -> Option<Action> {
         let table_manager = &mut self.table_manager.lock().unwrap();
+        table_manager.collapse_range();
         table_manager.table.scroll_to_end();
         Some(Action::Render)
     }
 
-    fn pause_current_torrent(&mut self) -> Option<Action> {
-        if let Some(torrent) = self.table_manager.lock().unwrap().current_torrent() {
-            let torrent_id = torrent.id.clone();
-            match torrent.status() {
-                TorrentStatus::Stopped => {
-                    self.ctx
-                        .send_torrent_action(TorrentAction::Start(vec![torrent_id]));
-                    torrent.update_status(TorrentStatus::Downloading);
-                    return Some(Action::Render);
-                }
-                _ => {
-                    self.ctx
-                        .send_torrent_action(TorrentAction::Stop(vec![torrent_id]));
-                    torrent.update_status(TorrentStatus::Stopped);
-                    return Some(Action::Render);
-                }
+    fn cycle_sort_key(&self) -> Option<Action> {
+        self.table_manager.lock().unwrap().cycle_sort_key();
+        Some(Action::Render)
+    }
+
+    fn toggle_sort_order(&self) -> Option<Action> {
+        self.table_manager.lock().unwrap().toggle_sort_order();
+        Some(Action::Render)
+    }
+
+    fn cycle_status_filter(&self) -> Option<Action> {
+        self.table_manager.lock().unwrap().cycle_status_filter();
+        Some(Action::Render)
+    }
+
+    fn toggle_hide_finished(&self) -> Option<Action> {
+        self.table_manager.lock().unwrap().toggle_hide_finished();
+        Some(Action::Render)
+    }
+
+    fn move_in_queue(&self, queue_move: QueueMove) -> Option<Action> {
+        let mut table_manager = self.table_manager.lock().unwrap();
+        let last_position = table_manager.table.items.len().saturating_sub(1);
+        let mut torrents: Vec<_> = table_manager
+            .selected_or_current_torrents()
+            .into_iter()
+            .map(|torrent| (torrent.id.clone(), torrent.queue_position))
+            .collect();
+
+        // Moving the torrents one by one in this order keeps their relative order
+        torrents.sort_by_key(|(_, position)| *position);
+        if matches!(queue_move, QueueMove::Down | QueueMove::Top) {
+            torrents.reverse();
+        }
+
+        // They move by one as a block, so none moves if one is already at the end.
+        // Clamping them one by one would swap neighbours stuck there.
+        // The one closest to the end comes first either way
+        let at_end = match queue_move {
+            QueueMove::Up => torrents.first().is_some_and(|(_, position)| *position == 0),
+            QueueMove::Down => torrents
+                .first()
+                .is_some_and(|(_, position)| *position >= last_position),
+            QueueMove::Top | QueueMove::Bottom => false,
+        };
+        if at_end {
+            return None;
+        }
+
+        for (id, position) in torrents {
+            let new_position = match queue_move {
+                QueueMove::Up => position - 1,
+                QueueMove::Down => position + 1,
+                QueueMove::Top => 0,
+                QueueMove::Bottom => last_position,
+            };
+            let args = TorrentSetArgs::default().queue_position(new_position);
+            self.ctx
+                .send_torrent_action(TorrentAction::SetArgs(Box::new(args), Some(vec![id])));
+        }
+
+        self.ctx.activity.refresh();
+        None
+    }
+
+    fn toggle_alt_speed(&self) -> Option<Action> {
+        let alt_speed_enabled = &self.bottom_stats.alt_speed_enabled;
+        let enabled = {
+            let mut alt_speed_enabled = alt_speed_enabled.lock().unwrap();
+            *alt_speed_enabled = !*alt_speed_enabled;
+            *alt_speed_enabled
+        };
+
+        self.ctx.send_torrent_action(TorrentAction::SetAltSpeed(
+            enabled,
+            Arc::clone(alt_speed_enabled),
+        ));
+        Some(Action::Render)
+    }
+
+    fn open_download_dir(&self) -> Option<Action> {
+        let path = {
+            let mut table_manager = self.table_manager.lock().unwrap();
+            let torrent = table_manager.current_torrent()?;
+            let download_dir = PathBuf::from(&torrent.download_dir);
+            // Single-file torrents don't get a directory of their own
+            match download_dir.join(&torrent.torrent_name) {
+                path if path.is_dir() => path,
+                _ => download_dir,
+            }
+        };
+
+        let opener = &self.ctx.config.general.file_opener;
+        let result = Command::new(opener)
+            .arg(&path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        match result {
+            Ok(mut child) => {
+                // Reap it once it exits
+                std::thread::spawn(move || child.wait());
+                None
+            }
+            Err(e) => {
+                let msg = format!(
+                    "Failed to run \"{opener}\" on:\n\"{}\"\n{e}",
+                    path.display()
+                );
+                let error_popup = ErrorPopup::new("Failed to open the download directory", msg);
+                Some(Action::Error(Box::new(error_popup)))
             }
         }
+    }
+
+    fn toggle_selection(&self) -> Option<Action> {
+        self.table_manager.lock().unwrap().toggle_selection();
+        Some(Action::Render)
+    }
+
+    fn undo_delete(&self) -> Option<Action> {
+        self.ctx.send_torrent_action(TorrentAction::UndoDelete);
         None
     }
+
+    fn verify_torrents(&self) -> Option<Action> {
+        let torrent_ids = self.table_manager.lock().unwrap().selected_or_current_ids();
+        if torrent_ids.is_empty() {
+            return None;
+        }
+
+        self.ctx
+            .send_torrent_action(TorrentAction::Verify(torrent_ids));
+        None
+    }
+
+    fn pause_current_torrent(&mut self) -> Option<Action> {
+        let mut table_manager = self.table_manager.lock().unwrap();
+        let mut torrents = table_manager.selected_or_current_torrents();
+        if torrents.is_empty() {
+            return None;
+        }
+
+        let torrent_ids = torrents.iter().map(|torrent| torrent.id.clone()).collect();
+
+        // Start everything only if everything is stopped, otherwise stop the rest
+        if torrents
+            .iter()
+            .all(|torrent| torrent.status() == TorrentStatus::Stopped)
+        {
+            self.ctx
+                .send_torrent_action(TorrentAction::Start(torrent_ids));
+            for torrent in &mut torrents {
+                torrent.update_status(TorrentStatus::Downloading);
+            }
+        } else {
+            self.ctx
+                .send_torrent_action(TorrentAction::Stop(torrent_ids));
+            for torrent in &mut torrents {
+                torrent.update_status(TorrentStatus::Stopped);
+            }
+        }
+        Some(Action::Render)
+    }
+
+    fn start_now(&mut self) -> Option<Action> {
+        let mut table_manager = self.table_manager.lock().unwrap();
+        let mut torrents = table_manager.selected_or_current_torrents();
+        if torrents.is_empty() {
+            return None;
+        }
+
+        let torrent_ids = torrents.iter().map(|torrent| torrent.id.clone()).collect();
+        self.ctx
+            .send_torrent_action(TorrentAction::StartNow(torrent_ids));
+        for torrent in &mut torrents {
+            torrent.update_status(TorrentStatus::Downloading);
+        }
+        Some(Action::Render)
+    }
+
+    // Only the torrents that aren't running yet are counted and sent along
+    fn start_all_torrents(&mut self) -> Option<Action> {
+        let mut table_manager = self.table_manager.lock().unwrap();
+        let mut torrents: Vec<_> = table_manager
+            .table
+            .items
+            .iter_mut()
+            .filter(|torrent| torrent.status() == TorrentStatus::Stopped)
+            .collect();
+        if torrents.is_empty() {
+            return Some(Action::ShowStatus("All torrents are running".to_string()));
+        }
+
+        let torrent_ids = torrents.iter().map(|torrent| torrent.id.clone()).collect();
+        self.ctx
+            .send_torrent_action(TorrentAction::Start(torrent_ids));
+        for torrent in &mut torrents {
+            torrent.update_status(TorrentStatus::Downloading);
+        }
+        Some(Action::ShowStatus(format!(
+            "Started {} torrents",
+            torrents.len()
+        )))
+    }
+
+    fn stop_all_torrents(&mut self) -> Option<Action> {
+        let mut table_manager = self.table_manager.lock().unwrap();
+        let mut torrents: Vec<_> = table_manager
+            .table
+            .items
+            .iter_mut()
+            .filter(|torrent| torrent.status() != TorrentStatus::Stopped)
+            .collect();
+        if torrents.is_empty() {
+            return Some(Action::ShowStatus("All torrents are stopped".to_string()));
+        }
+
+        let torrent_ids = torrents.iter().map(|torrent| torrent.id.clone()).collect();
+        self.ctx
+            .send_torrent_action(TorrentAction::Stop(torrent_ids));
+        for torrent in &mut torrents {
+            torrent.update_status(TorrentStatus::Stopped);
+        }
+        Some(Action::ShowStatus(format!(
+            "Stopped {} torrents",
+            torrents.len()
+        )))
+    }
 }
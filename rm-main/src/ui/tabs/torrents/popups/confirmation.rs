@@ -0,0 +1,79 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    prelude::*,
+    widgets::{
+        block::{Position, Title},
+        Block, BorderType, Clear, Paragraph, Wrap,
+    },
+};
+
+use crate::{
+    action::Action,
+    app,
+    transmission::TorrentAction,
+    ui::{centered_rect, components::Component},
+};
+
+pub struct ConfirmationPopup {
+    ctx: app::Ctx,
+    title: String,
+    message: String,
+    // Sent only after the user explicitly confirms
+    on_confirm: Option<TorrentAction>,
+}
+
+impl ConfirmationPopup {
+    pub fn new(ctx: app::Ctx, title: String, message: String, on_confirm: TorrentAction) -> Self {
+        Self {
+            ctx,
+            title,
+            message,
+            on_confirm: Some(on_confirm),
+        }
+    }
+}
+
+impl Component for ConfirmationPopup {
+    fn handle_actions(&mut self, action: Action) -> Option<Action> {
+        match action {
+            Action::Input(input) => match input.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    if let Some(torrent_action) = self.on_confirm.take() {
+                        self.ctx.send_torrent_action(torrent_action);
+                    }
+                    Some(Action::Quit)
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Char('q') | KeyCode::Esc => {
+                    Some(Action::Quit)
+                }
+                _ => None,
+            },
+            _ if action.is_soft_quit() => Some(action),
+            _ => None,
+        }
+    }
+
+    fn render(&mut self, f: &mut Frame, rect: Rect) {
+        let popup_rect = centered_rect(rect, 50, 30);
+        let block_rect = popup_rect.inner(&Margin::new(1, 1));
+        let text_rect = block_rect.inner(&Margin::new(3, 2));
+
+        let title_style = Style::default().fg(self.ctx.config.general.accent_color);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Title::from(
+                format!(" {} ", self.title).set_style(title_style),
+            ))
+            .title(
+                Title::from(" [y] - yes, [n] - no ".set_style(title_style.bold()))
+                    .alignment(Alignment::Right)
+                    .position(Position::Bottom),
+            );
+
+        let paragraph = Paragraph::new(&*self.message).wrap(Wrap { trim: false });
+
+        f.render_widget(Clear, popup_rect);
+        f.render_widget(block, block_rect);
+        f.render_widget(paragraph, text_rect);
+    }
+}
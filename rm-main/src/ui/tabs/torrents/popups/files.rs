@@ -11,16 +11,23 @@ use ratatui::{
         Block, BorderType, Clear, Paragraph,
     },
 };
-use transmission_rpc::types::{Id, Torrent, TorrentSetArgs};
+use transmission_rpc::types::{Id, Priority, Torrent, TorrentSetArgs};
 use tui_tree_widget::{Tree, TreeItem, TreeState};
 
+use rm_config::{
+    keymap::{GeneralAction, TorrentsAction},
+    Units,
+};
+
 use crate::{
     action::Action,
     app,
     transmission::TorrentAction,
-    ui::{centered_rect, components::Component},
+    ui::{centered_rect, components::Component, keybindings_footer},
 };
 
+use super::rename::RenamePopup;
+
 pub struct FilesPopup {
     ctx: app::Ctx,
     torrent: Arc<Mutex<Option<Torrent>>>,
@@ -29,6 +36,7 @@ pub struct FilesPopup {
     tree: Arc<Mutex<Node>>,
     current_focus: CurrentFocus,
     switched_after_fetched_data: bool,
+    rename_popup: Option<RenamePopup>,
 }
 
 async fn fetch_new_files(
@@ -89,9 +97,89 @@ impl FilesPopup {
             current_focus: CurrentFocus::CloseButton,
             switched_after_fetched_data: false,
             torrent_id,
+            rename_popup: None,
         }
     }
 
+    fn cycle_priority(&mut self) -> Option<Action> {
+        let file_id = self.tree_state.selected().last()?.parse::<usize>().ok()?;
+
+        let mut torrent = self.torrent.lock().unwrap();
+        let priorities = torrent.as_mut()?.priorities.as_mut()?;
+        let priority = priorities.get_mut(file_id)?;
+        *priority = match priority {
+            Priority::Normal => Priority::High,
+            Priority::High => Priority::Low,
+            Priority::Low => Priority::Normal,
+        };
+
+        for transmission_file in self.tree.lock().unwrap().get_by_ids(&[file_id]) {
+            transmission_file.priority = *priority;
+        }
+
+        let args = match priority {
+            Priority::Low => TorrentSetArgs::default().priority_low(vec![file_id]),
+            Priority::Normal => TorrentSetArgs::default().priority_normal(vec![file_id]),
+            Priority::High => TorrentSetArgs::default().priority_high(vec![file_id]),
+        };
+        self.ctx.send_torrent_action(TorrentAction::SetArgs(
+            Box::new(args),
+            Some(vec![self.torrent_id.clone()]),
+        ));
+
+        Some(Action::Render)
+    }
+
+    // Files under the highlighted row, be it a file or a directory
+    fn selected_file_ids(&self) -> Option<Vec<usize>> {
+        let selected = self.tree_state.selected();
+        let (last, parents) = selected.split_last()?;
+        let tree = self.tree.lock().unwrap();
+        match tree.get_by_path(parents)?.directories.get(last) {
+            Some(directory) => Some(directory.file_ids()),
+            None => last.parse().ok().map(|id| vec![id]),
+        }
+    }
+
+    // The highlighted file or directory as the daemon names it, starting
+    // with the torrent's directory
+    fn selected_path(&self) -> Option<String> {
+        let selected = self.tree_state.selected();
+        let (last, parents) = selected.split_last()?;
+        let tree = self.tree.lock().unwrap();
+        let parent = tree.get_by_path(parents)?;
+        let name = if parent.directories.contains_key(last) {
+            last.clone()
+        } else {
+            let file_id: usize = last.parse().ok()?;
+            let file = parent.items.iter().find(|file| file.id == file_id)?;
+            file.name.clone()
+        };
+        Some([parents, &[name]].concat().join("/"))
+    }
+
+    fn show_rename_popup(&mut self) -> Option<Action> {
+        let path = self.selected_path()?;
+        self.rename_popup = Some(RenamePopup::new(
+            self.ctx.clone(),
+            self.torrent_id.clone(),
+            path,
+        ));
+        Some(Action::SwitchToInputMode)
+    }
+
+    fn is_directory_selected(&self) -> bool {
+        let selected = self.tree_state.selected();
+        let Some((last, parents)) = selected.split_last() else {
+            return false;
+        };
+        self.tree
+            .lock()
+            .unwrap()
+            .get_by_path(parents)
+            .is_some_and(|parent| parent.directories.contains_key(last))
+    }
+
     fn switch_focus(&mut self) {
         match self.current_focus {
             CurrentFocus::CloseButton => self.current_focus = CurrentFocus::Files,
@@ -101,9 +189,18 @@ impl FilesPopup {
 }
 
 impl Component for FilesPopup {
-    #[must_use]
     fn handle_actions(&mut self, action: Action) -> Option<Action> {
         use Action as A;
+        if let Some(rename_popup) = &mut self.rename_popup {
+            return match rename_popup.handle_actions(action) {
+                Some(A::Quit) => {
+                    self.rename_popup = None;
+                    Some(A::SwitchToNormalMode)
+                }
+                action => action,
+            };
+        }
+
         match (action, self.current_focus) {
             (action, _) if action.is_soft_quit() => Some(A::Quit),
             (A::ChangeFocus, _) => {
@@ -111,25 +208,23 @@ impl Component for FilesPopup {
                 Some(A::Render)
             }
             (A::Confirm, CurrentFocus::CloseButton) => Some(A::Quit),
+            (A::Confirm, CurrentFocus::Files) if self.is_directory_selected() => {
+                self.tree_state.toggle_selected();
+                Some(A::Render)
+            }
             (A::Space | A::Confirm, CurrentFocus::Files) => {
                 if let Some(torrent) = &mut *self.torrent.lock().unwrap() {
                     let wanted_ids = torrent.wanted.as_mut().unwrap();
 
-                    let selected_ids: Vec<_> = self
-                        .tree_state
-                        .selected()
-                        .iter()
-                        .filter_map(|str_id| str_id.parse::<i32>().ok())
-                        .collect();
-
+                    // Toggling a directory toggles everything beneath it
+                    let selected_ids = self.selected_file_ids()?;
                     if selected_ids.is_empty() {
-                        self.tree_state.toggle_selected();
-                        return Some(A::Render);
+                        return None;
                     }
 
                     let mut wanted_in_selection_no = 0;
                     for selected_id in &selected_ids {
-                        if wanted_ids[*selected_id as usize] == 1 {
+                        if wanted_ids[*selected_id] {
                             wanted_in_selection_no += 1;
                         } else {
                             wanted_in_selection_no -= 1;
@@ -138,11 +233,11 @@ impl Component for FilesPopup {
 
                     if wanted_in_selection_no > 0 {
                         for selected_id in &selected_ids {
-                            wanted_ids[*selected_id as usize] = 0;
+                            wanted_ids[*selected_id] = false;
                         }
                     } else {
                         for selected_id in &selected_ids {
-                            wanted_ids[*selected_id as usize] = 1;
+                            wanted_ids[*selected_id] = true;
                         }
                     }
 
@@ -153,20 +248,14 @@ impl Component for FilesPopup {
                             {
                                 transmission_file.set_wanted(false);
                             }
-                            TorrentSetArgs {
-                                files_unwanted: Some(selected_ids),
-                                ..Default::default()
-                            }
+                            TorrentSetArgs::default().files_unwanted(selected_ids)
                         } else {
                             for transmission_file in
                                 self.tree.lock().unwrap().get_by_ids(&selected_ids)
                             {
                                 transmission_file.set_wanted(true);
                             }
-                            TorrentSetArgs {
-                                files_wanted: Some(selected_ids),
-                                ..Default::default()
-                            }
+                            TorrentSetArgs::default().files_wanted(selected_ids)
                         }
                     };
 
@@ -181,6 +270,9 @@ impl Component for FilesPopup {
                 }
             }
 
+            (A::CyclePriority, CurrentFocus::Files) => self.cycle_priority(),
+            (A::Rename, CurrentFocus::Files) => self.show_rename_popup(),
+
             (A::Up, CurrentFocus::Files) => {
                 self.tree_state.key_up();
                 Some(Action::Render)
@@ -233,12 +325,14 @@ impl Component for FilesPopup {
             };
 
             let download_dir = torrent.download_dir.as_ref().expect("Requested");
-            let keybinding_tip = {
-                if self.ctx.config.general.beginner_mode {
-                    "[SPACE] - select"
-                } else {
-                    ""
-                }
+            let keybinding_tip = if self.ctx.config.general.beginner_mode {
+                keybindings_footer(
+                    &self.ctx,
+                    &[GeneralAction::Select, GeneralAction::Confirm],
+                    &[TorrentsAction::CyclePriority, TorrentsAction::Rename],
+                )
+            } else {
+                Line::default()
             };
             let block = block
                 .title(
@@ -257,7 +351,7 @@ impl Component for FilesPopup {
                 );
 
             let tree_lock = self.tree.lock().unwrap();
-            let tree_items = tree_lock.make_tree();
+            let tree_items = tree_lock.make_tree(self.ctx.config.units());
 
             let tree_widget = Tree::new(&tree_items)
                 .unwrap()
@@ -266,6 +360,10 @@ impl Component for FilesPopup {
 
             f.render_widget(Clear, popup_rect);
             f.render_stateful_widget(tree_widget, block_rect, &mut self.tree_state);
+
+            if let Some(rename_popup) = &mut self.rename_popup {
+                rename_popup.render(f, rect);
+            }
         } else {
             let paragraph = Paragraph::new("Loading...");
             let block = block.title(
@@ -285,6 +383,9 @@ struct TransmissionFile {
     id: usize,
     // TODO: Change to enum
     wanted: bool,
+    priority: Priority,
+    length: i64,
+    bytes_completed: i64,
 }
 
 impl TransmissionFile {
@@ -313,12 +414,16 @@ impl Node {
         for (id, file) in files.iter().enumerate() {
             let path: Vec<String> = file.name.split('/').map(str::to_string).collect();
 
-            let wanted = torrent.wanted.as_ref().unwrap()[id] != 0;
+            let wanted = torrent.wanted.as_ref().unwrap()[id];
+            let priority = torrent.priorities.as_ref().unwrap()[id];
 
             let file = TransmissionFile {
                 id,
                 name: path[path.len() - 1].clone(),
                 wanted,
+                priority,
+                length: file.length,
+                bytes_completed: file.bytes_completed,
             };
 
             root.add_transmission_file(file, &path);
@@ -342,10 +447,40 @@ impl Node {
         }
     }
 
-    fn get_by_ids(&mut self, ids: &[i32]) -> Vec<&mut TransmissionFile> {
+    fn get_by_path(&self, path: &[String]) -> Option<&Self> {
+        match path.split_first() {
+            Some((first, rest)) => self.directories.get(first)?.get_by_path(rest),
+            None => Some(self),
+        }
+    }
+
+    fn file_ids(&self) -> Vec<usize> {
+        self.items
+            .iter()
+            .map(|file| file.id)
+            .chain(self.directories.values().flat_map(Self::file_ids))
+            .collect()
+    }
+
+    fn length(&self) -> i64 {
+        let files_length: i64 = self.items.iter().map(|file| file.length).sum();
+        files_length + self.directories.values().map(Self::length).sum::<i64>()
+    }
+
+    fn bytes_completed(&self) -> i64 {
+        let files_completed: i64 = self.items.iter().map(|file| file.bytes_completed).sum();
+        files_completed
+            + self
+                .directories
+                .values()
+                .map(Self::bytes_completed)
+                .sum::<i64>()
+    }
+
+    fn get_by_ids(&mut self, ids: &[usize]) -> Vec<&mut TransmissionFile> {
         let mut transmission_files = vec![];
         for file in &mut self.items {
-            if ids.contains(&(file.id as i32)) {
+            if ids.contains(&file.id) {
                 transmission_files.push(file);
             }
         }
@@ -355,22 +490,43 @@ impl Node {
         transmission_files
     }
 
-    fn make_tree(&self) -> Vec<TreeItem<String>> {
+    fn make_tree(&self, units: Units) -> Vec<TreeItem<'_, String>> {
         let mut tree_items = vec![];
         for transmission_file in &self.items {
-            let name = {
-                if transmission_file.wanted {
-                    format!("󰄲 {}", transmission_file.name)
-                } else {
-                    format!(" {}", transmission_file.name)
-                }
+            let checkbox = if transmission_file.wanted {
+                "󰄲"
+            } else {
+                ""
+            };
+            let progress = progress(transmission_file.bytes_completed, transmission_file.length);
+            let priority = match transmission_file.priority {
+                Priority::Low => " [low]",
+                Priority::Normal => "",
+                Priority::High => " [high]",
             };
+            let name = format!(
+                "{checkbox} {} ({}, {progress:.2}%){priority}",
+                transmission_file.name,
+                units.size(transmission_file.length),
+            );
             tree_items.push(TreeItem::new_leaf(transmission_file.id.to_string(), name));
         }
 
         for (key, value) in &self.directories {
-            tree_items.push(TreeItem::new(key.clone(), key.clone(), value.make_tree()).unwrap());
+            let name = format!(
+                "{key} ({}, {:.2}%)",
+                units.size(value.length()),
+                progress(value.bytes_completed(), value.length()),
+            );
+            tree_items.push(TreeItem::new(key.clone(), name, value.make_tree(units)).unwrap());
         }
         tree_items
     }
 }
+
+fn progress(bytes_completed: i64, length: i64) -> f64 {
+    match length {
+        0 => 100f64,
+        length => bytes_completed as f64 / length as f64 * 100f64,
+    }
+}
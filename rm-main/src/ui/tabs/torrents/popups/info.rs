@@ -0,0 +1,196 @@
+use std::sync::{Arc, Mutex};
+
+use ratatui::{
+    prelude::*,
+    widgets::{
+        block::{Position, Title},
+        Block, BorderType, Clear, Paragraph, Wrap,
+    },
+};
+use rm_config::{keymap::TorrentsAction, DateTimeFormat, Units};
+use transmission_rpc::types::{ErrorType, Id, Torrent, TrackerStat, TrackerState};
+use url::Url;
+
+use crate::{
+    action::Action,
+    app,
+    transmission::TorrentAction,
+    ui::{centered_rect, components::Component, keybindings_footer},
+    utils::seconds_to_human_format,
+};
+
+pub struct InfoPopup {
+    ctx: app::Ctx,
+    torrent: Arc<Mutex<Option<Torrent>>>,
+}
+
+impl InfoPopup {
+    pub fn new(ctx: app::Ctx, torrent_id: Id) -> Self {
+        let torrent = Arc::new(Mutex::new(None));
+
+        ctx.send_torrent_action(TorrentAction::GetTorrentInfo(
+            torrent_id,
+            Arc::clone(&torrent),
+        ));
+
+        Self { ctx, torrent }
+    }
+
+    pub(in crate::ui::tabs::torrents) fn torrent_to_lines(
+        torrent: &Torrent,
+        units: Units,
+        dates: &DateTimeFormat,
+    ) -> Vec<Line<'static>> {
+        fn line(key: &'static str, value: String) -> Line<'static> {
+            Line::from(vec![Span::raw(key).bold(), Span::raw(value)])
+        }
+
+        let mut lines = vec![];
+
+        if let Some(name) = &torrent.name {
+            lines.push(line("Name: ", name.clone()));
+        }
+        if let Some(hash) = &torrent.hash_string {
+            lines.push(line("Hash: ", hash.clone()));
+        }
+        if let Some(total_size) = torrent.total_size {
+            lines.push(line("Total size: ", units.size(total_size)));
+        }
+        if torrent.error.is_some_and(|error| error != ErrorType::Ok) {
+            let error_string = torrent.error_string.clone().unwrap_or_default();
+            lines.push(line("Error: ", error_string).red());
+        }
+        if let Some(download_dir) = &torrent.download_dir {
+            lines.push(line("Directory: ", download_dir.clone()));
+        }
+        if let Some(added_date) = torrent.added_date {
+            lines.push(line("Added: ", dates.format(added_date.timestamp())));
+        }
+        if let Some(done_date) = torrent.done_date {
+            lines.push(line("Completed: ", dates.format(done_date.timestamp())));
+        }
+        if let Some(ratio) = torrent.upload_ratio {
+            lines.push(line("Ratio: ", format!("{ratio:.2}")));
+        }
+        if let Some(peers_connected) = torrent.peers_connected {
+            let mut peers = peers_connected.to_string();
+            if let (Some(sending), Some(getting)) =
+                (torrent.peers_sending_to_us, torrent.peers_getting_from_us)
+            {
+                peers.push_str(&format!(" (▼{sending} from, ▲{getting} to)"));
+            }
+            lines.push(line("Peers connected: ", peers));
+        }
+        if let Some(eta) = torrent.eta {
+            let eta = match eta {
+                -2 => "∞".to_string(),
+                -1 => "-".to_string(),
+                eta_secs => seconds_to_human_format(eta_secs),
+            };
+            lines.push(line("ETA: ", eta));
+        }
+
+        if let Some(tracker_stats) = &torrent.tracker_stats {
+            lines.push(line("Trackers:", String::default()));
+            lines.extend(tracker_stats.iter().map(tracker_line));
+        } else if let Some(trackers) = &torrent.trackers {
+            lines.push(line("Trackers:", String::default()));
+            for tracker in trackers {
+                lines.push(Line::from(format!("  {}", tracker.announce)));
+            }
+        }
+
+        lines
+    }
+}
+
+// e.g. "  tier 1  tracker.example.org  Success, 50 peers (waiting)"
+fn tracker_line(tracker: &TrackerStat) -> Line<'static> {
+    let host = Url::parse(&tracker.announce)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| tracker.host.clone());
+
+    let (result, color) = if !tracker.has_announced {
+        ("not announced yet".to_string(), Color::Yellow)
+    } else if tracker.last_announce_succeeded {
+        let peers = tracker.last_announce_peer_count;
+        (
+            format!("{}, {peers} peers", tracker.last_announce_result),
+            Color::Green,
+        )
+    } else if tracker.last_announce_timed_out {
+        ("timed out".to_string(), Color::Yellow)
+    } else {
+        (tracker.last_announce_result.clone(), Color::Red)
+    };
+
+    let state = match tracker.announce_state {
+        TrackerState::Inactive => "inactive",
+        TrackerState::Waiting => "waiting",
+        TrackerState::Queued => "queued",
+        TrackerState::Active => "announcing",
+    };
+
+    // Transmission counts tiers from 0
+    Line::from(vec![
+        Span::raw(format!("  tier {}  {host}  ", tracker.tier + 1)),
+        Span::styled(result, Style::default().fg(color)),
+        Span::raw(format!(" ({state})")),
+    ])
+}
+
+impl Component for InfoPopup {
+    fn handle_actions(&mut self, action: Action) -> Option<Action> {
+        use Action as A;
+        match action {
+            _ if action.is_soft_quit() => Some(action),
+            A::Confirm => Some(Action::SoftQuit),
+            A::EditTrackers => Some(action),
+            _ => None,
+        }
+    }
+
+    fn render(&mut self, f: &mut Frame, rect: Rect) {
+        let popup_rect = centered_rect(rect, 75, 75);
+        let block_rect = popup_rect.inner(&Margin::new(1, 1));
+        let text_rect = block_rect.inner(&Margin::new(3, 2));
+
+        let title_style = Style::default().fg(self.ctx.config.general.accent_color);
+        let keybinding_tip = if self.ctx.config.general.beginner_mode {
+            keybindings_footer(&self.ctx, &[], &[TorrentsAction::EditTrackers])
+        } else {
+            Line::default()
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Title::from(" Info ".set_style(title_style)))
+            .title(
+                Title::from(" [ CLOSE ] ".set_style(title_style.bold()))
+                    .alignment(Alignment::Right)
+                    .position(Position::Bottom),
+            )
+            .title(
+                Title::from(keybinding_tip)
+                    .alignment(Alignment::Left)
+                    .position(Position::Bottom),
+            );
+
+        let paragraph = {
+            if let Some(torrent) = &*self.torrent.lock().unwrap() {
+                let lines = Self::torrent_to_lines(
+                    torrent,
+                    self.ctx.config.units(),
+                    &self.ctx.config.general.datetime_format,
+                );
+                Paragraph::new(lines).wrap(Wrap { trim: false })
+            } else {
+                Paragraph::new("Loading...")
+            }
+        };
+
+        f.render_widget(Clear, popup_rect);
+        f.render_widget(block, block_rect);
+        f.render_widget(paragraph, text_rect);
+    }
+}
@@ -1,10 +1,20 @@
-use self::{files::FilesPopup, stats::StatisticsPopup};
+use self::{
+    confirmation::ConfirmationPopup, files::FilesPopup, info::InfoPopup, rename::RenamePopup,
+    seed_limits::SeedLimitsPopup, speed_limit::SpeedLimitPopup, stats::StatisticsPopup,
+    trackers::TrackersPopup,
+};
 use crate::{action::Action, ui::components::Component};
 
 use ratatui::prelude::*;
 
+pub mod confirmation;
 pub mod files;
+pub mod info;
+pub mod rename;
+pub mod seed_limits;
+pub mod speed_limit;
 pub mod stats;
+pub mod trackers;
 
 pub struct PopupManager {
     current_popup: Option<CurrentPopup>,
@@ -13,6 +23,12 @@ pub struct PopupManager {
 pub enum CurrentPopup {
     Stats(StatisticsPopup),
     Files(FilesPopup),
+    Info(InfoPopup),
+    SpeedLimit(SpeedLimitPopup),
+    SeedLimits(SeedLimitsPopup),
+    Trackers(TrackersPopup),
+    Rename(RenamePopup),
+    Confirmation(ConfirmationPopup),
 }
 
 impl PopupManager {
@@ -35,48 +51,62 @@ impl PopupManager {
     }
 }
 
+impl CurrentPopup {
+    fn as_component(&mut self) -> &mut dyn Component {
+        match self {
+            Self::Stats(popup) => popup,
+            Self::Files(popup) => popup,
+            Self::Info(popup) => popup,
+            Self::SpeedLimit(popup) => popup,
+            Self::SeedLimits(popup) => popup,
+            Self::Trackers(popup) => popup,
+            Self::Rename(popup) => popup,
+            Self::Confirmation(popup) => popup,
+        }
+    }
+
+    // Popups with text inputs put the app into input mode when shown
+    const fn uses_input_mode(&self) -> bool {
+        matches!(
+            self,
+            Self::SpeedLimit(_)
+                | Self::SeedLimits(_)
+                | Self::Trackers(_)
+                | Self::Rename(_)
+                | Self::Confirmation(_)
+        )
+    }
+}
+
 impl Component for PopupManager {
-    #[must_use]
     fn handle_actions(&mut self, action: Action) -> Option<Action> {
-        if let Some(current_popup) = &mut self.current_popup {
-            match current_popup {
-                CurrentPopup::Stats(popup) => {
-                    if popup
-                        .handle_actions(action)
-                        .is_some_and(|a| a.is_soft_quit())
-                    {
-                        self.close_popup();
-                        return Some(Action::Render);
-                    };
-                }
-                CurrentPopup::Files(popup) => {
-                    if let Some(action) = popup.handle_actions(action) {
-                        match action {
-                            _ if action.is_soft_quit() => {
-                                self.close_popup();
-                                return Some(Action::Render);
-                            }
-                            Action::Render => return Some(Action::Render),
-                            _ => (),
-                        }
-                    }
+        let current_popup = self.current_popup.as_mut()?;
+
+        match current_popup.as_component().handle_actions(action) {
+            Some(action) if action.is_soft_quit() => {
+                let uses_input_mode = current_popup.uses_input_mode();
+                self.close_popup();
+                if uses_input_mode {
+                    Some(Action::SwitchToNormalMode)
+                } else {
+                    Some(Action::Render)
                 }
             }
-            return None;
+            Some(Action::Render) => Some(Action::Render),
+            // From a popup opened on top of the current one
+            Some(action @ (Action::SwitchToInputMode | Action::SwitchToNormalMode)) => Some(action),
+            // The info popup makes way for the trackers one
+            Some(Action::EditTrackers) => {
+                self.close_popup();
+                Some(Action::EditTrackers)
+            }
+            _ => None,
         }
-        None
     }
 
     fn render(&mut self, f: &mut Frame, rect: Rect) {
         if let Some(current_popup) = &mut self.current_popup {
-            match current_popup {
-                CurrentPopup::Stats(popup) => {
-                    popup.render(f, rect);
-                }
-                CurrentPopup::Files(popup) => {
-                    popup.render(f, rect);
-                }
-            }
+            current_popup.as_component().render(f, rect);
         }
     }
 }
@@ -0,0 +1,141 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{
+        block::{Position, Title},
+        Block, BorderType, Clear, Paragraph,
+    },
+};
+use transmission_rpc::types::Id;
+
+use crate::{
+    action::Action,
+    app,
+    transmission::TorrentAction,
+    ui::{
+        centered_rect,
+        components::{input_field::InputField, Component},
+        to_input_request,
+    },
+};
+
+// Renames the last part of a path in a torrent, be it the torrent itself
+// (its name is the path), a directory or a file
+pub struct RenamePopup {
+    ctx: app::Ctx,
+    torrent_id: Id,
+    path: String,
+    input: InputField,
+    error: Option<&'static str>,
+}
+
+impl RenamePopup {
+    pub fn new(ctx: app::Ctx, torrent_id: Id, path: String) -> Self {
+        let name = path.rsplit('/').next().unwrap_or_default().to_string();
+        Self {
+            input: InputField::new_with_value(ctx.clone(), "Rename to: ".to_string(), name),
+            ctx,
+            torrent_id,
+            path,
+            error: None,
+        }
+    }
+
+    fn rename(&mut self) -> Option<Action> {
+        let name = self.input.text();
+        if let Err(error) = validate_name(&name) {
+            self.error = Some(error);
+            return Some(Action::Render);
+        }
+
+        if self.path.rsplit('/').next() != Some(name.as_str()) {
+            self.ctx.send_torrent_action(TorrentAction::RenamePath(
+                self.torrent_id.clone(),
+                self.path.clone(),
+                name,
+            ));
+        }
+        Some(Action::Quit)
+    }
+
+    fn handle_input(&mut self, input: KeyEvent) -> Option<Action> {
+        match input.code {
+            KeyCode::Esc => Some(Action::Quit),
+            KeyCode::Enter => self.rename(),
+            _ => {
+                let req = to_input_request(input)?;
+                self.input.handle(req);
+                self.error = None;
+                Some(Action::Render)
+            }
+        }
+    }
+}
+
+// Only the name changes, moving it elsewhere in the torrent isn't supported
+fn validate_name(name: &str) -> Result<(), &'static str> {
+    if name.trim().is_empty() {
+        Err("The name can't be empty")
+    } else if name.contains(['/', '\\']) {
+        Err("The name can't contain / or \\")
+    } else if name == "." || name == ".." {
+        Err("The name can't be . or ..")
+    } else {
+        Ok(())
+    }
+}
+
+impl Component for RenamePopup {
+    fn handle_actions(&mut self, action: Action) -> Option<Action> {
+        match action {
+            Action::Input(input) => self.handle_input(input),
+            Action::Paste(text) => {
+                self.input.paste(&text);
+                self.error = None;
+                Some(Action::Render)
+            }
+            _ => None,
+        }
+    }
+
+    fn render(&mut self, f: &mut Frame, rect: Rect) {
+        let popup_rect = centered_rect(rect, 60, 30);
+        let block_rect = popup_rect.inner(&Margin::new(1, 1));
+        let text_rect = block_rect.inner(&Margin::new(3, 1));
+
+        let highlight_style = Style::default().fg(self.ctx.config.general.accent_color);
+        let keybinding_tip = {
+            if self.ctx.config.general.beginner_mode {
+                " [ENTER] - rename, [ESC] - cancel "
+            } else {
+                ""
+            }
+        };
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Title::from(" Rename ".set_style(highlight_style)))
+            .title(
+                Title::from(keybinding_tip)
+                    .alignment(Alignment::Left)
+                    .position(Position::Bottom),
+            );
+
+        f.render_widget(Clear, popup_rect);
+        f.render_widget(block, block_rect);
+
+        let [path_rect, input_rect, error_rect] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(text_rect);
+
+        f.render_widget(Paragraph::new(self.path.as_str()).dark_gray(), path_rect);
+        if let Some(error) = self.error {
+            let style = self.ctx.config.theme.error.to_style();
+            f.render_widget(Paragraph::new(error).style(style), error_rect);
+        }
+        self.input.render(f, input_rect);
+    }
+}
@@ -0,0 +1,355 @@
+use std::sync::{Arc, Mutex};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{
+        block::{Position, Title},
+        Block, BorderType, Clear, Paragraph,
+    },
+};
+use transmission_rpc::types::{Id, IdleMode, RatioMode, Torrent, TorrentSetArgs};
+use tui_input::InputRequest;
+
+use crate::{
+    action::Action,
+    app,
+    transmission::TorrentAction,
+    ui::{
+        centered_rect,
+        components::{input_field::InputField, Component},
+        to_input_request,
+    },
+};
+
+const RATIO_PROMPT: &str = "Stop at ratio: ";
+const IDLE_PROMPT: &str = "Stop when idle for (minutes): ";
+
+// Ratio and idle limits share the same three modes
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Global,
+    Single,
+    Unlimited,
+}
+
+impl Mode {
+    const fn next(self) -> Self {
+        match self {
+            Self::Global => Self::Single,
+            Self::Single => Self::Unlimited,
+            Self::Unlimited => Self::Global,
+        }
+    }
+
+    const fn previous(self) -> Self {
+        match self {
+            Self::Global => Self::Unlimited,
+            Self::Single => Self::Global,
+            Self::Unlimited => Self::Single,
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Global => "Global",
+            Self::Single => "Single",
+            Self::Unlimited => "Unlimited",
+        }
+    }
+
+    const fn from_ratio_mode(mode: RatioMode) -> Self {
+        match mode {
+            RatioMode::Global => Self::Global,
+            RatioMode::Single => Self::Single,
+            RatioMode::Unlimited => Self::Unlimited,
+        }
+    }
+
+    const fn from_idle_mode(mode: IdleMode) -> Self {
+        match mode {
+            IdleMode::Global => Self::Global,
+            IdleMode::Single => Self::Single,
+            IdleMode::Unlimited => Self::Unlimited,
+        }
+    }
+
+    const fn to_ratio_mode(self) -> RatioMode {
+        match self {
+            Self::Global => RatioMode::Global,
+            Self::Single => RatioMode::Single,
+            Self::Unlimited => RatioMode::Unlimited,
+        }
+    }
+
+    const fn to_idle_mode(self) -> IdleMode {
+        match self {
+            Self::Global => IdleMode::Global,
+            Self::Single => IdleMode::Single,
+            Self::Unlimited => IdleMode::Unlimited,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CurrentFocus {
+    Ratio,
+    Idle,
+}
+
+pub struct SeedLimitsPopup {
+    ctx: app::Ctx,
+    torrent: Arc<Mutex<Option<Torrent>>>,
+    torrent_ids: Vec<Id>,
+    ratio_mode: Mode,
+    ratio_input: InputField,
+    idle_mode: Mode,
+    idle_input: InputField,
+    current_focus: CurrentFocus,
+    filled_after_fetched_data: bool,
+}
+
+impl SeedLimitsPopup {
+    // Like the speed limits, the first torrent's limits are shown.
+    pub fn new(ctx: app::Ctx, torrent_ids: Vec<Id>) -> Self {
+        let torrent = Arc::new(Mutex::new(None));
+
+        ctx.send_torrent_action(TorrentAction::GetTorrentInfo(
+            torrent_ids[0].clone(),
+            Arc::clone(&torrent),
+        ));
+
+        Self {
+            ratio_input: InputField::new(ctx.clone(), RATIO_PROMPT.to_string()),
+            idle_input: InputField::new(ctx.clone(), IDLE_PROMPT.to_string()),
+            ctx,
+            torrent,
+            torrent_ids,
+            ratio_mode: Mode::Global,
+            idle_mode: Mode::Global,
+            current_focus: CurrentFocus::Ratio,
+            filled_after_fetched_data: false,
+        }
+    }
+
+    fn fill_inputs(&mut self, torrent: &Torrent) {
+        self.ratio_mode = torrent
+            .seed_ratio_mode
+            .map_or(Mode::Global, Mode::from_ratio_mode);
+        self.idle_mode = torrent
+            .seed_idle_mode
+            .map_or(Mode::Global, Mode::from_idle_mode);
+
+        self.ratio_input = InputField::new_with_value(
+            self.ctx.clone(),
+            RATIO_PROMPT.to_string(),
+            torrent
+                .seed_ratio_limit
+                .map(|limit| format!("{limit:.2}"))
+                .unwrap_or_default(),
+        );
+        self.idle_input = InputField::new_with_value(
+            self.ctx.clone(),
+            IDLE_PROMPT.to_string(),
+            torrent
+                .seed_idle_limit
+                .map(|limit| limit.to_string())
+                .unwrap_or_default(),
+        );
+    }
+
+    fn switch_focus(&mut self) {
+        match self.current_focus {
+            CurrentFocus::Ratio => self.current_focus = CurrentFocus::Idle,
+            CurrentFocus::Idle => self.current_focus = CurrentFocus::Ratio,
+        }
+    }
+
+    fn focused_mode(&mut self) -> &mut Mode {
+        match self.current_focus {
+            CurrentFocus::Ratio => &mut self.ratio_mode,
+            CurrentFocus::Idle => &mut self.idle_mode,
+        }
+    }
+
+    // The limits are only sent along when the torrent uses its own
+    fn send_limits(&self) {
+        let mut args = TorrentSetArgs::default()
+            .seed_ratio_mode(self.ratio_mode.to_ratio_mode())
+            .seed_idle_mode(self.idle_mode.to_idle_mode());
+        if self.ratio_mode == Mode::Single {
+            args.seed_ratio_limit = self.ratio_input.text().trim().parse().ok();
+        }
+        if self.idle_mode == Mode::Single {
+            args.seed_idle_limit = self.idle_input.text().trim().parse().ok();
+        }
+
+        self.ctx.send_torrent_action(TorrentAction::SetArgs(
+            Box::new(args),
+            Some(self.torrent_ids.clone()),
+        ));
+    }
+
+    fn handle_input(&mut self, input: KeyEvent) -> Option<Action> {
+        match input.code {
+            KeyCode::Esc => Some(Action::Quit),
+            // Don't let the user overwrite limits that haven't been fetched yet
+            _ if !self.filled_after_fetched_data => None,
+            KeyCode::Enter => {
+                self.send_limits();
+                Some(Action::Quit)
+            }
+            KeyCode::Tab | KeyCode::Up | KeyCode::Down => {
+                self.switch_focus();
+                Some(Action::Render)
+            }
+            KeyCode::Left => {
+                let mode = self.focused_mode();
+                *mode = mode.previous();
+                Some(Action::Render)
+            }
+            KeyCode::Right => {
+                let mode = self.focused_mode();
+                *mode = mode.next();
+                Some(Action::Render)
+            }
+            _ => {
+                let req = match (to_input_request(input), self.current_focus) {
+                    (Some(InputRequest::InsertChar(char)), CurrentFocus::Ratio)
+                        if !char.is_ascii_digit() && char != '.' =>
+                    {
+                        return None
+                    }
+                    (Some(InputRequest::InsertChar(char)), CurrentFocus::Idle)
+                        if !char.is_ascii_digit() =>
+                    {
+                        return None
+                    }
+                    (Some(req), _) => req,
+                    (None, _) => return None,
+                };
+
+                // Typing a limit means the torrent gets its own
+                *self.focused_mode() = Mode::Single;
+                match self.current_focus {
+                    CurrentFocus::Ratio => self.ratio_input.handle(req),
+                    CurrentFocus::Idle => self.idle_input.handle(req),
+                }
+                Some(Action::Render)
+            }
+        }
+    }
+
+    // Taken only if all of it could be typed into the focused field
+    fn handle_paste(&mut self, text: &str) -> Option<Action> {
+        let is_valid = |char: char| match self.current_focus {
+            CurrentFocus::Ratio => char.is_ascii_digit() || char == '.',
+            CurrentFocus::Idle => char.is_ascii_digit(),
+        };
+        if !self.filled_after_fetched_data || !text.chars().all(is_valid) {
+            return None;
+        }
+
+        *self.focused_mode() = Mode::Single;
+        match self.current_focus {
+            CurrentFocus::Ratio => self.ratio_input.paste(text),
+            CurrentFocus::Idle => self.idle_input.paste(text),
+        }
+        Some(Action::Render)
+    }
+
+    fn mode_line(&self, mode: Mode, is_focused: bool) -> Line<'static> {
+        let style = if is_focused {
+            Style::default()
+                .fg(self.ctx.config.general.accent_color)
+                .bold()
+        } else {
+            Style::default()
+        };
+        Line::from(format!("< {:<9} >", mode.name())).style(style)
+    }
+}
+
+impl Component for SeedLimitsPopup {
+    fn handle_actions(&mut self, action: Action) -> Option<Action> {
+        match action {
+            Action::Input(input) => self.handle_input(input),
+            Action::Paste(text) => self.handle_paste(text.trim()),
+            _ => None,
+        }
+    }
+
+    fn render(&mut self, f: &mut Frame, rect: Rect) {
+        let popup_rect = centered_rect(rect, 60, 30);
+        let block_rect = popup_rect.inner(&Margin::new(1, 1));
+        let text_rect = block_rect.inner(&Margin::new(3, 2));
+
+        let highlight_style = Style::default().fg(self.ctx.config.general.accent_color);
+        let keybinding_tip = {
+            if self.ctx.config.general.beginner_mode {
+                " [TAB] - switch field, [LEFT/RIGHT] - change mode, [ENTER] - confirm "
+            } else {
+                ""
+            }
+        };
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Title::from(" Seed limits ".set_style(highlight_style)))
+            .title(
+                Title::from(keybinding_tip)
+                    .alignment(Alignment::Left)
+                    .position(Position::Bottom),
+            );
+
+        f.render_widget(Clear, popup_rect);
+        f.render_widget(block, block_rect);
+
+        if !self.filled_after_fetched_data {
+            let torrent = self.torrent.lock().unwrap().take();
+            if let Some(torrent) = torrent {
+                self.fill_inputs(&torrent);
+                self.filled_after_fetched_data = true;
+            } else {
+                f.render_widget(Paragraph::new("Loading..."), text_rect);
+                return;
+            }
+        }
+
+        let [ratio_rect, idle_rect, _, tip_rect] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(text_rect);
+        let split = |rect| {
+            Layout::horizontal([Constraint::Length(14), Constraint::Min(0)]).areas::<2>(rect)
+        };
+        let [ratio_mode_rect, ratio_input_rect] = split(ratio_rect);
+        let [idle_mode_rect, idle_input_rect] = split(idle_rect);
+
+        let ratio_focused = self.current_focus == CurrentFocus::Ratio;
+        f.render_widget(
+            self.mode_line(self.ratio_mode, ratio_focused),
+            ratio_mode_rect,
+        );
+        f.render_widget(
+            self.mode_line(self.idle_mode, !ratio_focused),
+            idle_mode_rect,
+        );
+        f.render_widget(
+            Paragraph::new("Global uses the daemon's limits").dark_gray(),
+            tip_rect,
+        );
+
+        // Render the focused input last, so that it gets the cursor
+        if ratio_focused {
+            self.idle_input.render(f, idle_input_rect);
+            self.ratio_input.render(f, ratio_input_rect);
+        } else {
+            self.ratio_input.render(f, ratio_input_rect);
+            self.idle_input.render(f, idle_input_rect);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use std::sync::{Arc, Mutex};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{
+        block::{Position, Title},
+        Block, BorderType, Clear, Paragraph,
+    },
+};
+use transmission_rpc::types::{Id, SessionSetArgs, Torrent, TorrentSetArgs};
+use tui_input::InputRequest;
+
+use crate::{
+    action::Action,
+    app,
+    transmission::{SessionLimits, TorrentAction},
+    ui::{
+        centered_rect,
+        components::{input_field::InputField, Component},
+        to_input_request,
+    },
+};
+
+// Transmission takes limits in kB/s, 1000 bytes a second whatever the
+// configured units
+const DOWNLOAD_PROMPT: &str = "Download (kB/s): ";
+const UPLOAD_PROMPT: &str = "Upload (kB/s): ";
+
+pub struct SpeedLimitPopup {
+    ctx: app::Ctx,
+    torrent: Arc<Mutex<Option<Torrent>>>,
+    session_limits: Arc<Mutex<Option<SessionLimits>>>,
+    target: Target,
+    download_input: InputField,
+    upload_input: InputField,
+    current_focus: CurrentFocus,
+    filled_after_fetched_data: bool,
+}
+
+enum Target {
+    Torrents(Vec<Id>),
+    Session,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CurrentFocus {
+    Download,
+    Upload,
+}
+
+impl SpeedLimitPopup {
+    // The inputs are pre-filled with the limits of the first torrent.
+    pub fn new(ctx: app::Ctx, torrent_ids: Vec<Id>) -> Self {
+        let torrent = Arc::new(Mutex::new(None));
+
+        ctx.send_torrent_action(TorrentAction::GetTorrentInfo(
+            torrent_ids[0].clone(),
+            Arc::clone(&torrent),
+        ));
+
+        Self {
+            download_input: InputField::new(ctx.clone(), DOWNLOAD_PROMPT.to_string()),
+            upload_input: InputField::new(ctx.clone(), UPLOAD_PROMPT.to_string()),
+            ctx,
+            torrent,
+            session_limits: Arc::new(Mutex::new(None)),
+            target: Target::Torrents(torrent_ids),
+            current_focus: CurrentFocus::Download,
+            filled_after_fetched_data: false,
+        }
+    }
+
+    // Session-wide limits, pre-filled with the current ones
+    pub fn new_global(ctx: app::Ctx) -> Self {
+        let session_limits = Arc::new(Mutex::new(None));
+
+        ctx.send_torrent_action(TorrentAction::GetSessionLimits(Arc::clone(&session_limits)));
+
+        Self {
+            download_input: InputField::new(ctx.clone(), DOWNLOAD_PROMPT.to_string()),
+            upload_input: InputField::new(ctx.clone(), UPLOAD_PROMPT.to_string()),
+            ctx,
+            torrent: Arc::new(Mutex::new(None)),
+            session_limits,
+            target: Target::Session,
+            current_focus: CurrentFocus::Download,
+            filled_after_fetched_data: false,
+        }
+    }
+
+    // The limits as (limited, limit) for download and upload, once fetched
+    fn fetched_limits(&self) -> Option<[(Option<bool>, Option<u64>); 2]> {
+        match self.target {
+            Target::Torrents(_) => {
+                let torrent = self.torrent.lock().unwrap().take()?;
+                Some([
+                    (torrent.download_limited, torrent.download_limit),
+                    (torrent.upload_limited, torrent.upload_limit),
+                ])
+            }
+            Target::Session => {
+                let limits = self.session_limits.lock().unwrap().take()?;
+                Some([
+                    (
+                        Some(limits.speed_limit_down_enabled),
+                        Some(limits.speed_limit_down),
+                    ),
+                    (
+                        Some(limits.speed_limit_up_enabled),
+                        Some(limits.speed_limit_up),
+                    ),
+                ])
+            }
+        }
+    }
+
+    fn fill_inputs(&mut self, [download, upload]: [(Option<bool>, Option<u64>); 2]) {
+        fn limit_to_string((limited, limit): (Option<bool>, Option<u64>)) -> String {
+            match (limited, limit) {
+                (Some(true), Some(limit)) => limit.to_string(),
+                _ => String::default(),
+            }
+        }
+
+        self.download_input = InputField::new_with_value(
+            self.ctx.clone(),
+            DOWNLOAD_PROMPT.to_string(),
+            limit_to_string(download),
+        );
+        self.upload_input = InputField::new_with_value(
+            self.ctx.clone(),
+            UPLOAD_PROMPT.to_string(),
+            limit_to_string(upload),
+        );
+    }
+
+    fn switch_focus(&mut self) {
+        match self.current_focus {
+            CurrentFocus::Download => self.current_focus = CurrentFocus::Upload,
+            CurrentFocus::Upload => self.current_focus = CurrentFocus::Download,
+        }
+    }
+
+    // An empty input means unlimited.
+    fn parse_limit(text: &str) -> Option<usize> {
+        text.trim().parse().ok()
+    }
+
+    fn send_limits(&self) {
+        let download_limit = Self::parse_limit(&self.download_input.text());
+        let upload_limit = Self::parse_limit(&self.upload_input.text());
+
+        let torrent_ids = match &self.target {
+            Target::Torrents(torrent_ids) => torrent_ids,
+            Target::Session => {
+                let (speed_limit_down_enabled, speed_limit_down) = session_limit(download_limit);
+                let (speed_limit_up_enabled, speed_limit_up) = session_limit(upload_limit);
+                let args = SessionSetArgs {
+                    speed_limit_down_enabled,
+                    speed_limit_down,
+                    speed_limit_up_enabled,
+                    speed_limit_up,
+                    ..Default::default()
+                };
+                self.ctx
+                    .send_torrent_action(TorrentAction::SetSessionArgs(Box::new(args)));
+                return;
+            }
+        };
+
+        let mut args = TorrentSetArgs::default()
+            .download_limited(download_limit.is_some())
+            .upload_limited(upload_limit.is_some());
+        if let Some(download_limit) = download_limit {
+            args = args.download_limit(download_limit);
+        }
+        if let Some(upload_limit) = upload_limit {
+            args = args.upload_limit(upload_limit);
+        }
+
+        self.ctx.send_torrent_action(TorrentAction::SetArgs(
+            Box::new(args),
+            Some(torrent_ids.clone()),
+        ));
+    }
+
+    fn handle_input(&mut self, input: KeyEvent) -> Option<Action> {
+        match input.code {
+            KeyCode::Esc => Some(Action::Quit),
+            // Don't let the user overwrite limits that haven't been fetched yet
+            _ if !self.filled_after_fetched_data => None,
+            KeyCode::Enter => {
+                self.send_limits();
+                Some(Action::Quit)
+            }
+            KeyCode::Tab | KeyCode::Up | KeyCode::Down => {
+                self.switch_focus();
+                Some(Action::Render)
+            }
+            _ => match to_input_request(input) {
+                // Limits are whole kB/s, so accept digits only
+                Some(InputRequest::InsertChar(char)) if !char.is_ascii_digit() => None,
+                Some(req) => {
+                    match self.current_focus {
+                        CurrentFocus::Download => self.download_input.handle(req),
+                        CurrentFocus::Upload => self.upload_input.handle(req),
+                    }
+                    Some(Action::Render)
+                }
+                None => None,
+            },
+        }
+    }
+}
+
+// Enabled and the limit to send for a global limit
+fn session_limit(limit: Option<usize>) -> (Option<bool>, Option<i32>) {
+    match limit {
+        None => (Some(false), None),
+        Some(limit) => (Some(true), limit.try_into().ok()),
+    }
+}
+
+impl SpeedLimitPopup {
+    // Taken only if it's a whole number, like what can be typed
+    fn handle_paste(&mut self, text: &str) -> Option<Action> {
+        if !self.filled_after_fetched_data || !text.chars().all(|char| char.is_ascii_digit()) {
+            return None;
+        }
+        match self.current_focus {
+            CurrentFocus::Download => self.download_input.paste(text),
+            CurrentFocus::Upload => self.upload_input.paste(text),
+        }
+        Some(Action::Render)
+    }
+}
+
+impl Component for SpeedLimitPopup {
+    fn handle_actions(&mut self, action: Action) -> Option<Action> {
+        match action {
+            Action::Input(input) => self.handle_input(input),
+            Action::Paste(text) => self.handle_paste(text.trim()),
+            _ => None,
+        }
+    }
+
+    fn render(&mut self, f: &mut Frame, rect: Rect) {
+        let popup_rect = centered_rect(rect, 50, 30);
+        let block_rect = popup_rect.inner(&Margin::new(1, 1));
+        let text_rect = block_rect.inner(&Margin::new(3, 2));
+
+        let highlight_style = Style::default().fg(self.ctx.config.general.accent_color);
+        let keybinding_tip = {
+            if self.ctx.config.general.beginner_mode {
+                " [TAB] - switch field, [ENTER] - confirm "
+            } else {
+                ""
+            }
+        };
+
+        let title = match self.target {
+            Target::Torrents(_) => " Speed limits ",
+            Target::Session => " Global speed limits ",
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Title::from(title.set_style(highlight_style)))
+            .title(
+                Title::from(keybinding_tip)
+                    .alignment(Alignment::Left)
+                    .position(Position::Bottom),
+            );
+
+        f.render_widget(Clear, popup_rect);
+        f.render_widget(block, block_rect);
+
+        if !self.filled_after_fetched_data {
+            if let Some(limits) = self.fetched_limits() {
+                self.fill_inputs(limits);
+                self.filled_after_fetched_data = true;
+            } else {
+                f.render_widget(Paragraph::new("Loading..."), text_rect);
+                return;
+            }
+        }
+
+        let [download_rect, upload_rect, _, tip_rect] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(text_rect);
+
+        let tip = Paragraph::new("Leave empty for unlimited").dark_gray();
+        f.render_widget(tip, tip_rect);
+
+        // Render the focused input last, so that it gets the cursor
+        match self.current_focus {
+            CurrentFocus::Download => {
+                self.upload_input.render(f, upload_rect);
+                self.download_input.render(f, download_rect);
+            }
+            CurrentFocus::Upload => {
+                self.download_input.render(f, download_rect);
+                self.upload_input.render(f, upload_rect);
+            }
+        }
+    }
+}
@@ -7,11 +7,12 @@ use ratatui::{
 };
 use transmission_rpc::types::SessionStats;
 
+use rm_config::keymap::GeneralAction;
+
 use crate::{
     action::Action,
     app,
-    ui::{centered_rect, components::Component},
-    utils::bytes_to_human_format,
+    ui::{centered_rect, components::Component, keybindings_footer},
 };
 
 pub struct StatisticsPopup {
@@ -41,6 +42,11 @@ impl Component for StatisticsPopup {
         let text_rect = block_rect.inner(&Margin::new(3, 2));
 
         let title_style = Style::default().fg(self.ctx.config.general.accent_color);
+        let keybinding_tip = if self.ctx.config.general.beginner_mode {
+            keybindings_footer(&self.ctx, &[GeneralAction::SoftQuit], &[])
+        } else {
+            Line::default()
+        };
         let block = Block::bordered()
             .border_type(BorderType::Rounded)
             .title(Title::from(" Statistics ".set_style(title_style)))
@@ -48,12 +54,18 @@ impl Component for StatisticsPopup {
                 Title::from(" [ CLOSE ] ".set_style(title_style.bold()))
                     .alignment(Alignment::Right)
                     .position(Position::Bottom),
+            )
+            .title(
+                Title::from(keybinding_tip)
+                    .alignment(Alignment::Left)
+                    .position(Position::Bottom),
             );
 
         let uploaded_bytes = self.stats.cumulative_stats.uploaded_bytes;
         let downloaded_bytes = self.stats.cumulative_stats.downloaded_bytes;
-        let uploaded = bytes_to_human_format(uploaded_bytes);
-        let downloaded = bytes_to_human_format(downloaded_bytes);
+        let units = self.ctx.config.units();
+        let uploaded = units.size(uploaded_bytes);
+        let downloaded = units.size(downloaded_bytes);
         let ratio = uploaded_bytes as f64 / downloaded_bytes as f64;
         let text = format!("Uploaded: {uploaded}\nDownloaded: {downloaded}\nRatio: {ratio:.2}");
         let paragraph = Paragraph::new(text);
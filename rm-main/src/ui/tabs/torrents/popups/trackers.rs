@@ -0,0 +1,251 @@
+use std::sync::{Arc, Mutex};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{
+        block::{Position, Title},
+        Block, BorderType, Clear, List, ListState, Paragraph,
+    },
+};
+use transmission_rpc::types::{Id, Torrent, TorrentSetArgs, TrackerList};
+use url::Url;
+
+use crate::{
+    action::Action,
+    app,
+    transmission::TorrentAction,
+    ui::{
+        centered_rect,
+        components::{input_field::InputField, Component},
+        to_input_request,
+    },
+};
+
+const ADD_PROMPT: &str = "Add tracker: ";
+
+pub struct TrackersPopup {
+    ctx: app::Ctx,
+    torrent_id: Id,
+    torrent: Arc<Mutex<Option<Torrent>>>,
+    // Tier and announce url, None until first fetched
+    trackers: Option<Vec<(usize, String)>>,
+    list_state: ListState,
+    input: InputField,
+    error: Option<String>,
+}
+
+impl TrackersPopup {
+    pub fn new(ctx: app::Ctx, torrent_id: Id) -> Self {
+        let torrent = Arc::new(Mutex::new(None));
+
+        ctx.send_torrent_action(TorrentAction::GetTorrentInfo(
+            torrent_id.clone(),
+            Arc::clone(&torrent),
+        ));
+
+        Self {
+            input: InputField::new(ctx.clone(), ADD_PROMPT.to_string()),
+            ctx,
+            torrent_id,
+            torrent,
+            trackers: None,
+            list_state: ListState::default().with_selected(Some(0)),
+            error: None,
+        }
+    }
+
+    fn add_tracker(&mut self) -> Option<Action> {
+        let trackers = self.trackers.as_ref()?;
+        let announce = self.input.text().trim().to_string();
+        if let Err(error) = validate_announce_url(&announce) {
+            self.error = Some(error);
+            return Some(Action::Render);
+        }
+        if trackers.iter().any(|(_, url)| *url == announce) {
+            self.error = Some("The torrent already has this tracker".to_string());
+            return Some(Action::Render);
+        }
+
+        let mut args = TorrentSetArgs::default();
+        args.tracker_add = Some(vec![announce]);
+        self.input = InputField::new(self.ctx.clone(), ADD_PROMPT.to_string());
+        self.update_trackers(args)
+    }
+
+    // trackerRemove takes tracker ids, which transmission-rpc only lets us
+    // send as strings, so the whole list is sent without the removed one
+    fn remove_tracker(&mut self) -> Option<Action> {
+        let trackers = self.trackers.as_ref()?;
+        let selected = self
+            .list_state
+            .selected()
+            .filter(|selected| *selected < trackers.len())?;
+        let remaining: Vec<_> = trackers
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != selected)
+            .map(|(_, tracker)| tracker.clone())
+            .collect();
+
+        let mut args = TorrentSetArgs::default();
+        args.tracker_list = Some(tracker_list(remaining));
+        self.update_trackers(args)
+    }
+
+    // The list is fetched again once the daemon is done, failed or not.
+    // Until then the old one stays.
+    fn update_trackers(&mut self, args: TorrentSetArgs) -> Option<Action> {
+        self.error = None;
+        self.ctx.send_torrent_action(TorrentAction::SetTrackers(
+            Box::new(args),
+            self.torrent_id.clone(),
+            Arc::clone(&self.torrent),
+        ));
+        Some(Action::Render)
+    }
+
+    fn select(&mut self, down: bool) -> Option<Action> {
+        let len = self.trackers.as_ref()?.len();
+        let selected = self.list_state.selected().unwrap_or(0);
+        let selected = if down {
+            (selected + 1).min(len.saturating_sub(1))
+        } else {
+            selected.saturating_sub(1)
+        };
+        self.list_state.select(Some(selected));
+        Some(Action::Render)
+    }
+
+    fn handle_input(&mut self, input: KeyEvent) -> Option<Action> {
+        match input.code {
+            KeyCode::Esc => Some(Action::Quit),
+            _ if self.trackers.is_none() => None,
+            KeyCode::Enter => self.add_tracker(),
+            KeyCode::Up => self.select(false),
+            KeyCode::Down => self.select(true),
+            KeyCode::Delete if self.input.text().is_empty() => self.remove_tracker(),
+            _ => {
+                let req = to_input_request(input)?;
+                self.input.handle(req);
+                self.error = None;
+                Some(Action::Render)
+            }
+        }
+    }
+
+    fn fill_trackers(&mut self, torrent: &Torrent) {
+        let trackers: Vec<_> = torrent
+            .trackers
+            .iter()
+            .flatten()
+            .map(|tracker| (tracker.tier, tracker.announce.clone()))
+            .collect();
+        let last = trackers.len().saturating_sub(1);
+        let selected = self.list_state.selected().unwrap_or(0).min(last);
+        self.list_state.select(Some(selected));
+        self.trackers = Some(trackers);
+    }
+}
+
+// Transmission takes the trackers of a torrent as one announce url per line,
+// with an empty line between tiers
+fn tracker_list(mut trackers: Vec<(usize, String)>) -> TrackerList {
+    trackers.sort_by_key(|(tier, _)| *tier);
+    let mut lines = vec![];
+    let mut last_tier = None;
+    for (tier, announce) in trackers {
+        if last_tier.is_some_and(|last_tier| last_tier != tier) {
+            lines.push(String::new());
+        }
+        last_tier = Some(tier);
+        lines.push(announce);
+    }
+    TrackerList(lines)
+}
+
+fn validate_announce_url(announce: &str) -> Result<(), String> {
+    let url = Url::parse(announce).map_err(|e| format!("Invalid url: {e}"))?;
+    match url.scheme() {
+        "http" | "https" | "udp" => Ok(()),
+        scheme => Err(format!(
+            "Trackers can't use {scheme}://, only http, https and udp"
+        )),
+    }
+}
+
+impl Component for TrackersPopup {
+    fn handle_actions(&mut self, action: Action) -> Option<Action> {
+        match action {
+            Action::Input(input) => self.handle_input(input),
+            Action::Paste(text) if self.trackers.is_some() => {
+                self.input.paste(text.trim());
+                self.error = None;
+                Some(Action::Render)
+            }
+            _ => None,
+        }
+    }
+
+    fn render(&mut self, f: &mut Frame, rect: Rect) {
+        let popup_rect = centered_rect(rect, 75, 50);
+        let block_rect = popup_rect.inner(&Margin::new(1, 1));
+        let text_rect = block_rect.inner(&Margin::new(3, 2));
+
+        let highlight_style = Style::default().fg(self.ctx.config.general.accent_color);
+        let keybinding_tip = {
+            if self.ctx.config.general.beginner_mode {
+                " [ENTER] - add, [UP/DOWN] - pick, [DEL] - remove picked, [ESC] - close "
+            } else {
+                ""
+            }
+        };
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Title::from(" Trackers ".set_style(highlight_style)))
+            .title(
+                Title::from(keybinding_tip)
+                    .alignment(Alignment::Left)
+                    .position(Position::Bottom),
+            );
+
+        f.render_widget(Clear, popup_rect);
+        f.render_widget(block, block_rect);
+
+        let torrent = self.torrent.lock().unwrap().take();
+        if let Some(torrent) = torrent {
+            self.fill_trackers(&torrent);
+        }
+        let Some(trackers) = self.trackers.as_deref() else {
+            f.render_widget(Paragraph::new("Loading..."), text_rect);
+            return;
+        };
+
+        let [list_rect, _, input_rect, error_rect] = Layout::vertical([
+            Constraint::Min(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(text_rect);
+
+        if trackers.is_empty() {
+            f.render_widget(Paragraph::new("No trackers").dark_gray(), list_rect);
+        } else {
+            // Transmission counts tiers from 0
+            let items: Vec<_> = trackers
+                .iter()
+                .map(|(tier, announce)| format!("tier {}  {announce}", tier + 1))
+                .collect();
+            let list = List::new(items).highlight_style(highlight_style.on_black().bold());
+            f.render_stateful_widget(list, list_rect, &mut self.list_state);
+        }
+
+        if let Some(error) = &self.error {
+            let style = self.ctx.config.theme.error.to_style();
+            f.render_widget(Paragraph::new(error.as_str()).style(style), error_rect);
+        }
+        self.input.render(f, input_rect);
+    }
+}
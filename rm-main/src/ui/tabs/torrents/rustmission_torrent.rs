@@ -1,14 +1,45 @@
 use ratatui::{
-    style::{Style, Stylize},
-    text::{Line, Span},
+    style::{Color, Style, Stylize},
+    text::{Line, Span, Text},
     widgets::Row,
 };
-use transmission_rpc::types::{Id, Torrent, TorrentStatus};
+use rm_config::{Header, Theme, Units};
+use transmission_rpc::types::{ErrorType, Id, Torrent, TorrentStatus};
 
 use crate::utils::{
-    bytes_to_human_format, download_speed_format, seconds_to_human_format, upload_speed_format,
+    download_speed_format, seconds_to_human_format, truncate_line, upload_speed_format,
 };
 
+// How every row of the table is drawn
+pub struct RowLook<'a> {
+    pub headers: &'a [Header],
+    pub theme: &'a Theme,
+    // The progress is drawn as a bar if given its width
+    pub progress_bar_width: Option<u16>,
+    // Two lines per row, with a summary below the name
+    pub expanded: bool,
+    // As laid out in the last render, the selection mark's included
+    pub column_widths: &'a [u16],
+}
+
+impl RowLook<'_> {
+    // Cells that don't fit their column end with an ellipsis
+    fn fit<'a>(&self, column: usize, line: Line<'a>) -> Line<'a> {
+        match self.column_widths.get(column + 1) {
+            Some(width) => truncate_line(line, usize::from(*width)),
+            None => line,
+        }
+    }
+
+    pub const fn height(&self) -> u16 {
+        if self.expanded {
+            2
+        } else {
+            1
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct RustmissionTorrent {
     pub torrent_name: String,
@@ -17,100 +48,268 @@ pub struct RustmissionTorrent {
     pub eta_secs: String,
     pub download_speed: String,
     pub upload_speed: String,
+    pub upload_ratio: f32,
     status: TorrentStatus,
-    pub style: Style,
     pub id: Id,
+    pub error: ErrorType,
+    // What the daemon says went wrong, empty without an error
+    pub error_string: String,
+    pub download_dir: String,
+    pub queue_position: usize,
+    pub labels: Vec<String>,
+    pub peers: String,
+    // Raw values, used for sorting
+    pub size_when_done_bytes: i64,
+    pub percent_done: f32,
+    pub download_speed_bytes: i64,
+    pub upload_speed_bytes: i64,
 }
 
 impl RustmissionTorrent {
-    pub fn to_row(&self) -> ratatui::widgets::Row {
-        Row::new([
-            Line::from(self.torrent_name.as_str()),
-            Line::from(""),
-            Line::from(self.size_when_done.as_str()),
-            Line::from(self.progress.as_str()),
-            Line::from(self.eta_secs.as_str()),
-            Line::from(download_speed_format(&self.download_speed)),
-            Line::from(upload_speed_format(&self.upload_speed)),
-        ])
-        .style(self.style)
+    pub fn to_row(&self, look: &RowLook, is_selected: bool) -> ratatui::widgets::Row<'_> {
+        self.row_with_name(Line::from(self.torrent_name.as_str()), look, is_selected)
     }
 
     pub fn to_row_with_higlighted_indices(
         &self,
         highlighted_indices: Vec<usize>,
         highlight_style: Style,
-    ) -> ratatui::widgets::Row {
+        look: &RowLook,
+        is_selected: bool,
+    ) -> ratatui::widgets::Row<'_> {
+        let style = self.style(look.theme);
         let mut torrent_name_line = Line::default();
 
-        for (index, char) in self.torrent_name.char_indices() {
+        for (index, char) in self.torrent_name.chars().enumerate() {
             if highlighted_indices.contains(&index) {
                 torrent_name_line.push_span(Span::styled(char.to_string(), highlight_style));
             } else {
-                torrent_name_line.push_span(Span::styled(char.to_string(), self.style))
+                torrent_name_line.push_span(Span::styled(char.to_string(), style))
+            }
+        }
+
+        self.row_with_name(torrent_name_line, look, is_selected)
+    }
+
+    fn row_with_name<'a>(
+        &'a self,
+        name: Line<'a>,
+        look: &RowLook,
+        is_selected: bool,
+    ) -> ratatui::widgets::Row<'a> {
+        let cells = look.headers.iter().enumerate().map(|(column, header)| {
+            let fit = |line| look.fit(column, line);
+            match header {
+                Header::Name if look.expanded => {
+                    Text::from(vec![fit(name.clone()), fit(self.summary_line())])
+                }
+                Header::Name => Text::from(fit(name.clone())),
+                header => Text::from(fit(self.header_to_line(*header, look.progress_bar_width))),
+            }
+        });
+
+        Row::new(std::iter::once(Text::from(Self::selection_mark(is_selected))).chain(cells))
+            .height(look.height())
+            .style(self.style(look.theme))
+    }
+
+    // Shown below the name in expanded rows, whatever the columns are
+    fn summary_line(&self) -> Line<'_> {
+        let mut summary = self.status_text().to_string();
+        // Right after the status, so a narrow column doesn't cut it off first
+        if self.error != ErrorType::Ok && !self.error_string.is_empty() {
+            summary.push_str(": ");
+            summary.push_str(&self.error_string);
+        }
+        summary.push_str(&format!(" · {} of {}", self.progress, self.size_when_done));
+        for speed in [
+            download_speed_format(&self.download_speed),
+            upload_speed_format(&self.upload_speed),
+        ] {
+            if !speed.is_empty() {
+                summary.push_str(" · ");
+                summary.push_str(&speed);
+            }
+        }
+        Line::from(summary).dark_gray()
+    }
+
+    fn header_to_line(&self, header: Header, progress_bar_width: Option<u16>) -> Line<'_> {
+        match header {
+            Header::Name => Line::from(self.torrent_name.as_str()),
+            Header::Size => Line::from(self.size_when_done.as_str()),
+            Header::Progress => match progress_bar_width {
+                Some(width) => self.progress_bar_line(width),
+                None => Line::from(self.progress.as_str()),
+            },
+            Header::Eta => Line::from(self.eta_secs.as_str()),
+            Header::Download => Line::from(download_speed_format(&self.download_speed)),
+            Header::Upload => Line::from(upload_speed_format(&self.upload_speed)),
+            Header::Ratio => self.ratio_line(),
+            Header::Queue => Line::from(self.queue_position.to_string()),
+            Header::Labels => Line::from(self.labels.join(", ")),
+            Header::Status => Line::from(self.status_text()),
+            Header::Peers => Line::from(self.peers.as_str()),
+        }
+    }
+
+    // An error takes precedence, it's what needs attention
+    pub fn status_text(&self) -> &'static str {
+        match (self.error, self.status) {
+            (ErrorType::TrackerWarning, _) => "Tracker warning",
+            (ErrorType::TrackerError, _) => "Tracker error",
+            (ErrorType::LocalError, _) => "Error",
+            (ErrorType::Ok, TorrentStatus::Stopped) => "Stopped",
+            (ErrorType::Ok, TorrentStatus::QueuedToVerify) => "Check wait",
+            (ErrorType::Ok, TorrentStatus::Verifying) => "Checking",
+            (ErrorType::Ok, TorrentStatus::QueuedToDownload | TorrentStatus::QueuedToSeed) => {
+                "Queued"
+            }
+            (ErrorType::Ok, TorrentStatus::Downloading) => "Downloading",
+            (ErrorType::Ok, TorrentStatus::Seeding) => "Seeding",
+        }
+    }
+
+    fn progress_bar_line(&self, width: u16) -> Line<'_> {
+        if matches!(
+            self.status,
+            TorrentStatus::Verifying | TorrentStatus::QueuedToVerify
+        ) {
+            return Line::from(self.progress.as_str());
+        }
+
+        let percent = format!("{:.0}%", self.percent_done * 100f32);
+        // Room for the brackets and the space before the percentage
+        let bar_len = usize::from(width).saturating_sub(percent.len() + 3);
+        let filled = ((bar_len as f32 * self.percent_done).round() as usize).min(bar_len);
+        let bar = format!(
+            "[{}{}] {percent}",
+            "#".repeat(filled),
+            "-".repeat(bar_len - filled)
+        );
+
+        match self.status {
+            TorrentStatus::Seeding => Line::from(bar).fg(Color::Green),
+            TorrentStatus::Downloading => Line::from(bar).fg(Color::Blue),
+            TorrentStatus::Stopped => Line::from(bar).fg(Color::DarkGray),
+            _ => Line::from(bar),
+        }
+    }
+
+    fn ratio_line(&self) -> Line<'_> {
+        // Transmission uses negative values for "not available" and "infinite"
+        match self.upload_ratio {
+            -1f32 => Line::from("-"),
+            -2f32 => Line::from("∞"),
+            ratio => {
+                let color = if ratio < 1f32 {
+                    Color::Red
+                } else if ratio <= 2f32 {
+                    Color::Yellow
+                } else {
+                    Color::Green
+                };
+                Line::from(format!("{ratio:.2}")).fg(color)
             }
         }
+    }
 
-        Row::new([
-            Line::from(torrent_name_line),
-            Line::from(""),
-            Line::from(self.size_when_done.as_str()),
-            Line::from(self.progress.as_str()),
-            Line::from(self.eta_secs.as_str()),
-            Line::from(download_speed_format(&self.download_speed)),
-            Line::from(upload_speed_format(&self.upload_speed)),
-        ])
+    fn selection_mark(is_selected: bool) -> Line<'static> {
+        if is_selected {
+            Line::from("✓")
+        } else {
+            Line::from("")
+        }
     }
 
     pub const fn status(&self) -> TorrentStatus {
         self.status
     }
 
+    // Fully downloaded and not seeding
+    pub fn is_finished(&self) -> bool {
+        self.percent_done >= 1f32 && self.status == TorrentStatus::Stopped
+    }
+
     pub fn update_status(&mut self, new_status: TorrentStatus) {
-        if new_status == TorrentStatus::Stopped {
-            self.style = Style::default().dark_gray().italic();
+        self.status = new_status;
+    }
+
+    fn style(&self, theme: &Theme) -> Style {
+        let status_style = match self.status {
+            TorrentStatus::Stopped => theme.stopped,
+            TorrentStatus::Verifying | TorrentStatus::QueuedToVerify => theme.verifying,
+            TorrentStatus::Downloading | TorrentStatus::QueuedToDownload => theme.downloading,
+            TorrentStatus::Seeding | TorrentStatus::QueuedToSeed => theme.seeding,
+        };
+
+        let style = status_style.to_style();
+        if self.error == ErrorType::Ok {
+            style
         } else {
-            self.style = Style::default();
+            style.patch(theme.error.to_style())
         }
-
-        self.status = new_status;
     }
 }
 
-impl From<&Torrent> for RustmissionTorrent {
-    fn from(t: &Torrent) -> Self {
+impl RustmissionTorrent {
+    pub fn new(t: &Torrent, units: Units) -> Self {
         let id = t.id().expect("id requested");
 
         let torrent_name = t.name.clone().expect("name requested");
 
-        let size_when_done = bytes_to_human_format(t.size_when_done.expect("field requested"));
+        let size_when_done_bytes = t.size_when_done.expect("field requested");
+        let size_when_done = units.size(size_when_done_bytes);
+
+        let status = t.status.expect("field requested");
 
-        let progress = match t.percent_done.expect("field requested") {
-            done if done == 1f32 => String::default(),
-            percent => format!("{:.2}%", percent * 100f32),
+        let percent_done = t.percent_done.expect("field requested");
+        let progress = match (status, percent_done) {
+            (TorrentStatus::Verifying, _) => "Checking".to_string(),
+            (TorrentStatus::QueuedToVerify, _) => "Check wait".to_string(),
+            (_, 1f32) => String::default(),
+            (_, percent) => format!("{:.2}%", percent * 100f32),
         };
 
-        let eta_secs = match t.eta.expect("field requested") {
-            -2 => "∞".to_string(),
-            -1 => String::default(),
-            eta_secs => seconds_to_human_format(eta_secs),
+        // There is nothing left to wait for in seeding or stopped torrents.
+        // Not requested without its column.
+        let eta_secs = match (status, t.eta) {
+            (_, None) => String::default(),
+            (TorrentStatus::Seeding | TorrentStatus::Stopped, _) => "-".to_string(),
+            (_, Some(-2)) => "∞".to_string(),
+            (_, Some(-1)) => String::default(),
+            (_, Some(eta_secs)) => seconds_to_human_format(eta_secs),
         };
 
-        let download_speed = match t.rate_download.expect("field requested") {
+        let download_speed_bytes = t.rate_download.expect("field requested");
+        let download_speed = match download_speed_bytes {
             0 => String::default(),
-            down => bytes_to_human_format(down),
+            down => units.speed(down),
         };
 
-        let upload_speed = match t.rate_upload.expect("field requested") {
+        let upload_speed_bytes = t.rate_upload.expect("field requested");
+        let upload_speed = match upload_speed_bytes {
             0 => String::default(),
-            upload => bytes_to_human_format(upload),
+            upload => units.speed(upload),
         };
 
-        let status = t.status.expect("field requested");
+        let upload_ratio = t.upload_ratio.expect("field requested");
+
+        let error = t.error.expect("field requested");
+
+        let error_string = t.error_string.clone().unwrap_or_default();
+
+        let download_dir = t.download_dir.clone().expect("field requested");
+
+        let queue_position = t.queue_position.expect("field requested");
+
+        // Daemons older than 3.0 don't know about labels
+        let labels = t.labels.clone().unwrap_or_default();
 
-        let style = match status {
-            TorrentStatus::Stopped => Style::default().dark_gray().italic(),
-            _ => Style::default(),
+        // Not requested without its column
+        let peers = match (t.peers_sending_to_us, t.peers_getting_from_us) {
+            (Some(sending), Some(getting)) => format!("▼{sending} ▲{getting}"),
+            _ => String::default(),
         };
 
         Self {
@@ -120,9 +319,19 @@ impl From<&Torrent> for RustmissionTorrent {
             eta_secs,
             download_speed,
             upload_speed,
+            upload_ratio,
             status,
-            style,
             id,
+            error,
+            error_string,
+            download_dir,
+            queue_position,
+            labels,
+            peers,
+            size_when_done_bytes,
+            percent_done,
+            download_speed_bytes,
+            upload_speed_bytes,
         }
     }
 }
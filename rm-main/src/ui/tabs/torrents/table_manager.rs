@@ -1,120 +1,645 @@
-use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
-use ratatui::{prelude::*, widgets::Row};
-use std::sync::{Arc, Mutex};
+use ratatui::{layout::Flex, prelude::*, widgets::Row};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
 
-use crate::{app, ui::components::table::GenericTable};
+use rm_config::{ColumnWidth, Config, Header};
+use transmission_rpc::types::{ErrorType, Id, TorrentStatus};
+use unicode_width::UnicodeWidthStr;
 
-use super::rustmission_torrent::RustmissionTorrent;
+use crate::{
+    app,
+    ui::{components::table::GenericTable, matcher::Matcher, state::UiState},
+    utils::{download_speed_format, truncate_line, upload_speed_format},
+};
+
+use super::rustmission_torrent::{RowLook, RustmissionTorrent};
 
 pub struct TableManager {
     ctx: app::Ctx,
     pub table: GenericTable<RustmissionTorrent>,
-    pub widths: [Constraint; 7],
+    pub widths: Vec<ColumnWidth>,
+    // What the widths came to in the last render
+    pub column_widths: Vec<u16>,
     pub filter: Arc<Mutex<Option<String>>>,
     pub torrents_displaying_no: u16,
-    header: Vec<String>,
+    pub sort_by: SortKey,
+    pub sort_reverse: bool,
+    pub status_filter: StatusFilter,
+    // Leaves out torrents that are done and stopped
+    pub hide_finished: bool,
+    // Torrents marked for batch operations
+    pub selected: HashSet<Id>,
+    // Where a shift+arrow range started, None if the marks were made one by one
+    range_anchor: Option<Id>,
+    pub expanded_rows: bool,
+    headers: Vec<Header>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Downloading,
+    Seeding,
+    Stopped,
+    Error,
+}
+
+impl StatusFilter {
+    const fn next(self) -> Self {
+        match self {
+            Self::All => Self::Downloading,
+            Self::Downloading => Self::Seeding,
+            Self::Seeding => Self::Stopped,
+            Self::Stopped => Self::Error,
+            Self::Error => Self::All,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::All => "All",
+            Self::Downloading => "Downloading",
+            Self::Seeding => "Seeding",
+            Self::Stopped => "Stopped",
+            Self::Error => "Error",
+        }
+    }
+
+    fn matches(self, torrent: &RustmissionTorrent) -> bool {
+        match self {
+            Self::All => true,
+            Self::Downloading => matches!(
+                torrent.status(),
+                TorrentStatus::Downloading | TorrentStatus::QueuedToDownload
+            ),
+            Self::Seeding => matches!(
+                torrent.status(),
+                TorrentStatus::Seeding | TorrentStatus::QueuedToSeed
+            ),
+            Self::Stopped => torrent.status() == TorrentStatus::Stopped,
+            Self::Error => torrent.error != ErrorType::Ok,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SortKey {
+    #[default]
+    Name,
+    Size,
+    Progress,
+    DownloadSpeed,
+    UploadSpeed,
+    Status,
+}
+
+impl SortKey {
+    const fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Size,
+            Self::Size => Self::Progress,
+            Self::Progress => Self::DownloadSpeed,
+            Self::DownloadSpeed => Self::UploadSpeed,
+            Self::UploadSpeed => Self::Status,
+            Self::Status => Self::Name,
+        }
+    }
+
+    // Column this key sorts by
+    const fn header(self) -> Option<Header> {
+        match self {
+            Self::Name => Some(Header::Name),
+            Self::Size => Some(Header::Size),
+            Self::Progress => Some(Header::Progress),
+            Self::DownloadSpeed => Some(Header::Download),
+            Self::UploadSpeed => Some(Header::Upload),
+            Self::Status => Some(Header::Status),
+        }
+    }
+
+    fn compare(self, a: &RustmissionTorrent, b: &RustmissionTorrent) -> Ordering {
+        match self {
+            Self::Name => a
+                .torrent_name
+                .to_lowercase()
+                .cmp(&b.torrent_name.to_lowercase()),
+            Self::Size => a.size_when_done_bytes.cmp(&b.size_when_done_bytes),
+            Self::Progress => a.percent_done.total_cmp(&b.percent_done),
+            Self::DownloadSpeed => a.download_speed_bytes.cmp(&b.download_speed_bytes),
+            Self::UploadSpeed => a.upload_speed_bytes.cmp(&b.upload_speed_bytes),
+            Self::Status => a.status().cmp(&b.status()),
+        }
+    }
 }
 
 impl TableManager {
     pub fn new(ctx: app::Ctx, table: GenericTable<RustmissionTorrent>) -> Self {
-        let widths = Self::default_widths();
+        let headers = ctx.config.torrents_tab.columns.clone();
+        let widths = Self::default_widths(&ctx.config, &headers);
+        let state = UiState::load();
+        let expanded_rows = ctx.config.torrents_tab.expanded_rows;
         Self {
             ctx,
             table,
             widths,
+            column_widths: vec![],
             filter: Arc::new(Mutex::new(None)),
             torrents_displaying_no: 0,
-            header: vec![
-                "Name".to_owned(),
-                "".to_owned(),
-                "Size".to_owned(),
-                "Progress".to_owned(),
-                "ETA".to_owned(),
-                "Download".to_owned(),
-                "Upload".to_owned(),
-            ],
+            sort_by: state.sort_by,
+            sort_reverse: state.sort_reverse,
+            status_filter: state.status_filter,
+            hide_finished: state.hide_finished,
+            selected: HashSet::new(),
+            range_anchor: None,
+            expanded_rows,
+            headers,
         }
     }
 
     pub fn rows(&self) -> Vec<Row<'_>> {
-        if let Some(filter) = &*self.filter.lock().unwrap() {
-            let rows = self.filtered_torrents_rows(&self.table.items, filter);
-            self.table.overwrite_len(rows.len());
-            rows
-        } else {
-            self.table
-                .items
-                .iter()
-                .map(RustmissionTorrent::to_row)
-                .collect()
-        }
+        let look = self.row_look();
+        let highlight_style = Style::default()
+            .fg(self.ctx.config.general.accent_color)
+            .bold();
+
+        let rows: Vec<_> = self
+            .visible_torrents()
+            .into_iter()
+            .map(|(idx, indices)| {
+                let torrent = &self.table.items[idx];
+                let is_selected = self.selected.contains(&torrent.id);
+                match indices {
+                    Some(indices) => torrent.to_row_with_higlighted_indices(
+                        indices,
+                        highlight_style,
+                        &look,
+                        is_selected,
+                    ),
+                    None => torrent.to_row(&look, is_selected),
+                }
+            })
+            .collect();
+
+        self.table.overwrite_len(rows.len());
+        self.clamp_selection(rows.len());
+        rows
+    }
+
+    // Sums of what's shown, so that they follow the filters
+    pub fn totals_row(&self) -> Row<'static> {
+        let rows = self.visible_rows();
+        let units = self.ctx.config.units();
+        let sum = |bytes: fn(&RustmissionTorrent) -> i64| rows.iter().map(|row| bytes(row)).sum();
+        let speed = |bytes: i64| match bytes {
+            0 => String::default(),
+            bytes => units.speed(bytes),
+        };
+
+        let size: i64 = sum(|row| row.size_when_done_bytes);
+        let download = speed(sum(|row| row.download_speed_bytes));
+        let upload = speed(sum(|row| row.upload_speed_bytes));
+        let cells = self.headers.iter().enumerate().map(|(column, header)| {
+            let total = match header {
+                Header::Name => format!("{} torrents", rows.len()),
+                Header::Size => units.size(size),
+                Header::Download => download_speed_format(&download),
+                Header::Upload => upload_speed_format(&upload),
+                _ => String::default(),
+            };
+            match self.column_widths.get(column + 1) {
+                Some(width) => truncate_line(Line::from(total), usize::from(*width)),
+                None => Line::from(total),
+            }
+        });
+
+        std::iter::once(Line::default())
+            .chain(cells)
+            .collect::<Row>()
     }
 
-    pub const fn header(&self) -> &Vec<String> {
-        &self.header
+    pub fn header(&self) -> Vec<String> {
+        let arrow = if self.sort_reverse { "▼" } else { "▲" };
+        let status_hidden =
+            self.sort_by == SortKey::Status && !self.headers.contains(&Header::Status);
+
+        // The first column holds the selection marks
+        let header = self
+            .headers
+            .iter()
+            .map(|header| match self.sort_by.header() {
+                _ if status_hidden && *header == Header::Name => {
+                    format!("{} (Status {arrow})", header.name())
+                }
+                Some(sort_header) if sort_header == *header => format!("{} {arrow}", header.name()),
+                _ => header.name().to_owned(),
+            });
+
+        std::iter::once(String::default()).chain(header).collect()
     }
 
     pub fn current_torrent(&mut self) -> Option<&mut RustmissionTorrent> {
-        let matcher = SkimMatcherV2::default();
         let index = self.table.state.borrow().selected()?;
+        let visible_index = *self.visible_indices().get(index)?;
+        self.table.items.get_mut(visible_index)
+    }
 
-        if let Some(filter) = &*self.filter.lock().unwrap() {
-            let mut loop_index = 0;
-            for rustmission_torrent in &mut self.table.items {
-                if matcher
-                    .fuzzy_match(&rustmission_torrent.torrent_name, filter)
-                    .is_some()
-                {
-                    if index == loop_index {
-                        return Some(rustmission_torrent);
-                    }
-                    loop_index += 1;
-                }
+    pub fn toggle_selection(&mut self) {
+        if let Some(torrent) = self.current_torrent() {
+            let id = torrent.id.clone();
+            if !self.selected.remove(&id) {
+                self.selected.insert(id);
+            }
+            // The range's marks are kept as if made one by one
+            self.range_anchor = None;
+        }
+    }
+
+    // Moves the highlight by a row and marks every torrent between it and the
+    // anchor, which is where the first shifted move started.
+    pub fn extend_selection(&mut self, down: bool) {
+        let Some(current_id) = self.current_torrent().map(|torrent| torrent.id.clone()) else {
+            return;
+        };
+        let visible = self.visible_indices();
+        let items = &self.table.items;
+        let position = |id: &Id| visible.iter().position(|&idx| items[idx].id == *id);
+        // An anchor that got filtered out starts the range anew
+        let (anchor_id, anchor) = self
+            .range_anchor
+            .take()
+            .and_then(|id| Some((id.clone(), position(&id)?)))
+            .unwrap_or_else(|| {
+                let current = position(&current_id).unwrap_or_default();
+                (current_id, current)
+            });
+
+        if down {
+            self.table.scroll_down_by(1, false);
+        } else {
+            self.table.scroll_up_by(1, false);
+        }
+
+        let current = self.table.state.borrow().selected().unwrap_or_default();
+        self.selected = visible
+            .get(anchor.min(current)..=anchor.max(current))
+            .unwrap_or_default()
+            .iter()
+            .map(|&idx| self.table.items[idx].id.clone())
+            .collect();
+        self.range_anchor = Some(anchor_id);
+    }
+
+    // Plain movements end a range along with its marks, while the ones made
+    // one by one stay
+    pub fn collapse_range(&mut self) {
+        if self.range_anchor.take().is_some() {
+            self.selected.clear();
+        }
+    }
+
+    // Batch operations apply to the marked torrents, or to the highlighted one if none are marked.
+    pub fn selected_or_current_torrents(&mut self) -> Vec<&mut RustmissionTorrent> {
+        if self.selected.is_empty() {
+            return self.current_torrent().into_iter().collect();
+        }
+
+        let selected = &self.selected;
+        self.table
+            .items
+            .iter_mut()
+            .filter(|torrent| selected.contains(&torrent.id))
+            .collect()
+    }
+
+    pub fn selected_or_current_ids(&mut self) -> Vec<Id> {
+        self.selected_or_current_torrents()
+            .into_iter()
+            .map(|torrent| torrent.id.clone())
+            .collect()
+    }
+
+    pub fn cycle_status_filter(&mut self) {
+        self.refilter(|table_manager| {
+            table_manager.status_filter = table_manager.status_filter.next()
+        });
+    }
+
+    pub fn toggle_hide_finished(&mut self) {
+        self.refilter(|table_manager| table_manager.hide_finished = !table_manager.hide_finished);
+    }
+
+    fn refilter(&mut self, change: impl FnOnce(&mut Self)) {
+        // Remember where the selected torrent was, so that we can snap
+        // to the nearest torrent that is still visible.
+        let selected_index = self
+            .table
+            .state
+            .borrow()
+            .selected()
+            .and_then(|index| self.visible_indices().get(index).copied());
+
+        change(self);
+
+        let visible_indices = self.visible_indices();
+        let new_selection = selected_index
+            .and_then(|selected_index| {
+                visible_indices
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, idx)| idx.abs_diff(selected_index))
+                    .map(|(visible_idx, _)| visible_idx)
+            })
+            .unwrap_or(0);
+
+        self.table.overwrite_len(visible_indices.len());
+        self.table.state.borrow_mut().select(Some(new_selection));
+        self.save_state();
+    }
+
+    // In the order they're shown
+    pub fn visible_rows(&self) -> Vec<&RustmissionTorrent> {
+        self.visible_indices()
+            .into_iter()
+            .map(|idx| &self.table.items[idx])
+            .collect()
+    }
+
+    // Indices into `table.items` of torrents passing both the status and the text filter.
+    fn visible_indices(&self) -> Vec<usize> {
+        self.visible_torrents()
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    // Same as `visible_indices`, in display order, along with the characters
+    // to highlight when filtering by text.
+    fn visible_torrents(&self) -> Vec<(usize, Option<Vec<usize>>)> {
+        let filter_mode = self.ctx.config.torrents_tab.filter_mode;
+        // An invalid pattern filters nothing, the filter bar points it out
+        let matcher = self
+            .filter
+            .lock()
+            .unwrap()
+            .as_deref()
+            .and_then(|filter| Matcher::new(filter_mode, filter).ok());
+
+        let mut visible: Vec<_> = self
+            .table
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, torrent)| self.status_filter.matches(torrent))
+            .filter(|(_, torrent)| !(self.hide_finished && torrent.is_finished()))
+            .filter_map(|(idx, torrent)| match &matcher {
+                Some(matcher) => matcher
+                    .matches(&torrent.torrent_name)
+                    .map(|(score, indices)| (idx, score, Some(indices))),
+                None => Some((idx, 0, None)),
+            })
+            .collect();
+
+        // Best matches first, the sort being stable keeps the order of equal ones
+        visible.sort_by_key(|(_, score, _)| Reverse(*score));
+        visible
+            .into_iter()
+            .map(|(idx, _, indices)| (idx, indices))
+            .collect()
+    }
+
+    fn clamp_selection(&self, len: usize) {
+        let mut state = self.table.state.borrow_mut();
+        if let Some(selected) = state.selected() {
+            if len > 0 && selected >= len {
+                state.select(Some(len - 1));
             }
-            return None;
         }
-        self.table.items.get_mut(index)
+    }
+
+    // Where the highlighted torrent is shown, and which one it is
+    fn current_position(&self) -> Option<(usize, Id)> {
+        let index = self.table.state.borrow().selected()?;
+        let item_index = *self.visible_indices().get(index)?;
+        Some((index, self.table.items[item_index].id.clone()))
+    }
+
+    // Rows are selected by index, so follow the torrent to wherever it moved.
+    // If it's gone, the row that took its place is highlighted instead.
+    fn restore_position(&self, position: Option<(usize, Id)>) {
+        let Some((index, id)) = position else {
+            return;
+        };
+        let visible_ids = self
+            .visible_indices()
+            .into_iter()
+            .map(|item_index| &self.table.items[item_index].id);
+        let new_index = restored_index(visible_ids, index, &id);
+        self.table.state.borrow_mut().select(Some(new_index));
     }
 
     pub fn set_new_rows(&mut self, rows: Vec<RustmissionTorrent>) {
-        self.table.items = rows;
+        let position = self.current_position();
+        merge_rows(&mut self.table.items, rows, |torrent| &torrent.id, true);
+        self.forget_marks();
+        self.sort_rows();
+        self.restore_position(position);
+        self.widths = self.header_widths(&self.table.items);
+    }
+
+    // Only the torrents that changed, the others stay as they are
+    pub fn apply_changes(&mut self, rows: Vec<RustmissionTorrent>, removed: &[i64]) {
+        let position = self.current_position();
+        merge_rows(&mut self.table.items, rows, |torrent| &torrent.id, false);
+        self.table
+            .items
+            .retain(|torrent| !matches!(torrent.id, Id::Id(id) if removed.contains(&id)));
+        self.forget_marks();
+        self.sort_rows();
+        self.restore_position(position);
         self.widths = self.header_widths(&self.table.items);
     }
 
-    fn filtered_torrents_rows<'a>(
-        &self,
-        torrents: &'a [RustmissionTorrent],
-        filter: &str,
-    ) -> Vec<Row<'a>> {
-        let matcher = SkimMatcherV2::default();
-        let mut rows = vec![];
+    fn forget_marks(&mut self) {
+        // Forget marks of torrents that are gone
+        let items = &self.table.items;
+        self.selected
+            .retain(|id| items.iter().any(|torrent| &torrent.id == id));
+    }
+
+    pub fn cycle_sort_key(&mut self) {
+        let position = self.current_position();
+        self.sort_by = self.sort_by.next();
+        self.sort_rows();
+        self.restore_position(position);
+        self.save_state();
+    }
+
+    pub fn toggle_sort_order(&mut self) {
+        let position = self.current_position();
+        self.sort_reverse = !self.sort_reverse;
+        self.sort_rows();
+        self.restore_position(position);
+        self.save_state();
+    }
+
+    fn save_state(&self) {
+        UiState::update(|state| {
+            state.sort_by = self.sort_by;
+            state.sort_reverse = self.sort_reverse;
+            state.status_filter = self.status_filter;
+            state.hide_finished = self.hide_finished;
+        });
+    }
 
-        let highlight_style = Style::default().fg(self.ctx.config.general.accent_color);
+    // `sort_by` is stable, so torrents with equal keys keep their order
+    // instead of swapping places between fetches.
+    fn sort_rows(&mut self) {
+        let sort_by = self.sort_by;
+        let sort_reverse = self.sort_reverse;
 
-        for torrent in torrents {
-            if let Some((_, indices)) = matcher.fuzzy_indices(&torrent.torrent_name, filter) {
-                rows.push(torrent.to_row_with_higlighted_indices(indices, highlight_style))
+        self.table.items.sort_by(|a, b| {
+            let ordering = sort_by.compare(a, b);
+            if sort_reverse {
+                ordering.reverse()
+            } else {
+                ordering
             }
+        });
+    }
+
+    fn row_look(&self) -> RowLook<'_> {
+        RowLook {
+            headers: &self.headers,
+            theme: &self.ctx.config.theme,
+            progress_bar_width: self.progress_bar_width(),
+            expanded: self.expanded_rows,
+            column_widths: &self.column_widths,
         }
+    }
 
-        rows
+    // The same way the table widget would, the table is then given these as
+    // fixed widths. Ranges that came out too narrow get their minimum.
+    pub fn lay_out_columns(&mut self, rect: Rect) {
+        let lay_out = |constraints: &[Constraint]| -> Vec<u16> {
+            Layout::horizontal(constraints)
+                .flex(Flex::Start)
+                .spacing(1)
+                .split(rect)
+                .iter()
+                .map(|column| column.width)
+                .collect()
+        };
+
+        let mut constraints = self.constraints();
+        let mut column_widths = lay_out(&constraints);
+        let mut widened = false;
+        for ((constraint, column_width), width) in
+            constraints.iter_mut().zip(&column_widths).zip(&self.widths)
+        {
+            if *column_width < width.min() {
+                *constraint = Constraint::Length(width.min());
+                widened = true;
+            }
+        }
+        if widened {
+            column_widths = lay_out(&constraints);
+        }
+        self.column_widths = column_widths;
     }
 
-    const fn default_widths() -> [Constraint; 7] {
-        [
-            Constraint::Max(70),    // Name
-            Constraint::Length(5),  // <padding>
-            Constraint::Length(12), // Size
-            Constraint::Length(12), // Progress
-            Constraint::Length(12), // ETA
-            Constraint::Length(12), // Download
-            Constraint::Length(12), // Upload
-        ]
+    // Shares adding up to more than the whole table can't be honored, so every
+    // shown column gets an equal one then
+    fn constraints(&self) -> Vec<Constraint> {
+        let total_percentage: u16 = self
+            .widths
+            .iter()
+            .filter_map(|width| width.percentage())
+            .sum();
+        if total_percentage <= 100 {
+            return self.widths.iter().map(|width| width.constraint()).collect();
+        }
+
+        let is_hidden = |width: &ColumnWidth| *width == ColumnWidth::Length(0);
+        // Skip the selection mark column
+        let shown = self.widths[1..]
+            .iter()
+            .filter(|width| !is_hidden(width))
+            .count();
+        std::iter::once(Constraint::Length(1))
+            .chain(self.widths[1..].iter().map(|width| {
+                if is_hidden(width) {
+                    Constraint::Length(0)
+                } else {
+                    Constraint::Ratio(1, shown as u32)
+                }
+            }))
+            .collect()
+    }
+
+    // The highlighted torrent's name if it's cut in the table. None when every
+    // visible name fits, so that no line is taken for nothing.
+    pub fn full_name_footer(&self) -> Option<String> {
+        let name_idx = self
+            .headers
+            .iter()
+            .position(|header| *header == Header::Name)?;
+        let name_width = usize::from(*self.column_widths.get(name_idx + 1)?);
+        let is_cut = |torrent: &&RustmissionTorrent| torrent.torrent_name.width() > name_width;
+
+        let rows = self.visible_rows();
+        if !rows.iter().any(is_cut) {
+            return None;
+        }
+
+        let current = self
+            .table
+            .state
+            .borrow()
+            .selected()
+            .and_then(|index| rows.get(index).copied());
+        Some(
+            current
+                .filter(is_cut)
+                .map(|torrent| torrent.torrent_name.clone())
+                .unwrap_or_default(),
+        )
+    }
+
+    pub fn row_height(&self) -> u16 {
+        self.row_look().height()
     }
 
-    fn header_widths(&self, rows: &[RustmissionTorrent]) -> [Constraint; 7] {
+    fn progress_bar_width(&self) -> Option<u16> {
+        if !self.ctx.config.torrents_tab.progress_bar {
+            return None;
+        }
+
+        let progress_idx = self
+            .headers
+            .iter()
+            .position(|header| *header == Header::Progress)?;
+        // Skip the selection mark column
+        self.column_widths.get(progress_idx + 1).copied()
+    }
+
+    fn default_widths(config: &Config, headers: &[Header]) -> Vec<ColumnWidth> {
+        std::iter::once(ColumnWidth::Length(1)) // <selection mark>
+            .chain(
+                headers
+                    .iter()
+                    .map(|header| config.torrents_tab.column_width(*header)),
+            )
+            .collect()
+    }
+
+    fn header_widths(&self, rows: &[RustmissionTorrent]) -> Vec<ColumnWidth> {
         if !self.ctx.config.general.auto_hide {
-            return Self::default_widths();
+            return Self::default_widths(&self.ctx.config, &self.headers);
         }
 
         let mut download_width = 0;
@@ -129,23 +654,172 @@ impl TableManager {
             if !row.upload_speed.is_empty() {
                 upload_width = 11;
             }
-            if !row.progress.is_empty() {
+            // Bars are drawn for finished torrents as well
+            if !row.progress.is_empty() || self.ctx.config.torrents_tab.progress_bar {
                 progress_width = 11;
             }
 
-            if !row.eta_secs.is_empty() {
+            if !row.eta_secs.is_empty() && row.eta_secs != "-" {
                 eta_width = 11;
             }
         }
 
-        [
-            Constraint::Max(70),                // Name
-            Constraint::Length(5),              // <padding>
-            Constraint::Length(11),             // Size
-            Constraint::Length(progress_width), // Progress
-            Constraint::Length(eta_width),      // ETA
-            Constraint::Length(download_width), // Download
-            Constraint::Length(upload_width),   // Upload
-        ]
+        // A configured width is kept for the columns that aren't hidden
+        let torrents_tab = &self.ctx.config.torrents_tab;
+        let auto_width =
+            |header: Header, width: u16| match (width, torrents_tab.widths.get(&header)) {
+                (0, _) => ColumnWidth::Length(0),
+                (_, Some(configured)) => *configured,
+                (width, None) => ColumnWidth::Length(width),
+            };
+        let header_width = |header: &Header| match header {
+            Header::Size => auto_width(*header, 11),
+            Header::Progress => auto_width(*header, progress_width),
+            Header::Eta => auto_width(*header, eta_width),
+            Header::Download => auto_width(*header, download_width),
+            Header::Upload => auto_width(*header, upload_width),
+            header => torrents_tab.column_width(*header),
+        };
+
+        std::iter::once(ColumnWidth::Length(1)) // <selection mark>
+            .chain(self.headers.iter().map(header_width))
+            .collect()
+    }
+}
+
+// Torrents still there are overwritten where they are and new ones are added
+// at the end, instead of rebuilding the whole list every fetch. Without
+// `drop_missing`, torrents that didn't come are kept.
+fn merge_rows<T>(items: &mut Vec<T>, rows: Vec<T>, id: fn(&T) -> &Id, drop_missing: bool) {
+    let positions: HashMap<Id, usize> = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| (id(item).clone(), index))
+        .collect();
+
+    let mut is_present = vec![!drop_missing; items.len()];
+    for row in rows {
+        match positions.get(id(&row)) {
+            Some(&index) => {
+                items[index] = row;
+                is_present[index] = true;
+            }
+            None => {
+                items.push(row);
+                is_present.push(true);
+            }
+        }
+    }
+
+    let mut is_present = is_present.into_iter();
+    items.retain(|_| is_present.next().unwrap_or_default());
+}
+
+// Index of `id` among the visible rows, or the one nearest to `index`
+fn restored_index<'a>(
+    visible_ids: impl ExactSizeIterator<Item = &'a Id>,
+    index: usize,
+    id: &Id,
+) -> usize {
+    let last_index = visible_ids.len().saturating_sub(1);
+    let mut visible_ids = visible_ids;
+    visible_ids
+        .position(|visible_id| visible_id == id)
+        .unwrap_or_else(|| index.min(last_index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Row = (Id, &'static str);
+
+    fn row_id(row: &Row) -> &Id {
+        &row.0
+    }
+
+    fn rows(ids_and_names: &[(i64, &'static str)]) -> Vec<Row> {
+        ids_and_names
+            .iter()
+            .map(|&(id, name)| (Id::Id(id), name))
+            .collect()
+    }
+
+    fn ids(items: &[Row]) -> Vec<Id> {
+        items.iter().map(|row| row.0.clone()).collect()
+    }
+
+    #[test]
+    fn updates_rows_in_place() {
+        let mut items = rows(&[(1, "a"), (2, "b"), (3, "c")]);
+
+        merge_rows(
+            &mut items,
+            rows(&[(2, "b2"), (3, "c2"), (1, "a2")]),
+            row_id,
+            true,
+        );
+
+        assert_eq!(items, rows(&[(1, "a2"), (2, "b2"), (3, "c2")]));
+    }
+
+    #[test]
+    fn adds_new_rows_at_the_end() {
+        let mut items = rows(&[(1, "a"), (2, "b")]);
+
+        merge_rows(
+            &mut items,
+            rows(&[(4, "d"), (1, "a"), (2, "b")]),
+            row_id,
+            true,
+        );
+
+        assert_eq!(items, rows(&[(1, "a"), (2, "b"), (4, "d")]));
+    }
+
+    #[test]
+    fn drops_rows_that_did_not_come() {
+        let mut items = rows(&[(1, "a"), (2, "b"), (3, "c")]);
+
+        merge_rows(&mut items, rows(&[(3, "c2"), (1, "a")]), row_id, true);
+
+        assert_eq!(items, rows(&[(1, "a"), (3, "c2")]));
+    }
+
+    #[test]
+    fn keeps_rows_that_did_not_come() {
+        let mut items = rows(&[(1, "a"), (2, "b"), (3, "c")]);
+
+        merge_rows(&mut items, rows(&[(3, "c2"), (4, "d")]), row_id, false);
+
+        assert_eq!(items, rows(&[(1, "a"), (2, "b"), (3, "c2"), (4, "d")]));
+    }
+
+    #[test]
+    fn keeps_the_selected_torrent_selected() {
+        let mut items = rows(&[(1, "a"), (2, "b"), (3, "c")]);
+        let selected = Id::Id(2);
+
+        merge_rows(
+            &mut items,
+            rows(&[(5, "e"), (3, "c"), (2, "b")]),
+            row_id,
+            true,
+        );
+        let ids = ids(&items);
+
+        assert_eq!(ids, [Id::Id(2), Id::Id(3), Id::Id(5)]);
+        assert_eq!(restored_index(ids.iter(), 1, &selected), 0);
+    }
+
+    #[test]
+    fn selects_the_row_that_took_the_place_of_a_removed_one() {
+        let mut items = rows(&[(1, "a"), (2, "b"), (3, "c")]);
+
+        merge_rows(&mut items, rows(&[(1, "a"), (3, "c")]), row_id, true);
+        assert_eq!(restored_index(ids(&items).iter(), 1, &Id::Id(2)), 1);
+
+        merge_rows(&mut items, rows(&[(1, "a")]), row_id, true);
+        assert_eq!(restored_index(ids(&items).iter(), 1, &Id::Id(3)), 0);
     }
 }
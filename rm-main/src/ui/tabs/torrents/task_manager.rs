@@ -1,15 +1,20 @@
 use std::sync::{Arc, Mutex};
 
 use ratatui::prelude::*;
+use rm_config::ConfirmAction;
 
-use crate::{action::Action, app, ui::components::Component};
+use crate::{
+    action::Action,
+    app,
+    transmission::TorrentAction,
+    ui::{components::Component, global_popups::ErrorPopup},
+};
 
 use super::{
     tasks::{
-        add_magnet::AddMagnetBar,
-        default::DefaultBar,
-        delete_torrent::{self, DeleteBar},
-        filter::FilterBar,
+        add_magnet::AddMagnetBar, default::DefaultBar, delete_torrent::DeleteBar,
+        filter::FilterBar, go_to_row::GoToRowBar, labels::LabelsBar, move_torrent::MoveBar,
+        status::StatusBar,
     },
     TableManager,
 };
@@ -34,11 +39,14 @@ enum CurrentTask {
     AddMagnetBar(AddMagnetBar),
     DeleteBar(DeleteBar),
     FilterBar(FilterBar),
+    MoveBar(MoveBar),
+    LabelsBar(LabelsBar),
+    GoToRowBar(GoToRowBar),
+    Status(StatusBar),
     Default(DefaultBar),
 }
 
 impl Component for TaskManager {
-    #[must_use]
     fn handle_actions(&mut self, action: Action) -> Option<Action> {
         use Action as A;
         match &mut self.current_task {
@@ -60,6 +68,34 @@ impl Component for TaskManager {
                 _ => None,
             },
 
+            CurrentTask::MoveBar(move_bar) => match move_bar.handle_actions(action) {
+                Some(A::Quit) => self.finish_task(),
+                Some(A::Render) => Some(A::Render),
+                _ => None,
+            },
+
+            CurrentTask::LabelsBar(labels_bar) => match labels_bar.handle_actions(action) {
+                Some(A::Quit) => self.finish_task(),
+                Some(A::Render) => Some(A::Render),
+                _ => None,
+            },
+
+            CurrentTask::GoToRowBar(go_to_row_bar) => match go_to_row_bar.handle_actions(action) {
+                Some(A::Quit) => self.finish_task(),
+                Some(A::Render) => Some(A::Render),
+                _ => None,
+            },
+
+            CurrentTask::Status(status_bar) => match action {
+                A::Tick => match status_bar.tick() {
+                    Some(A::Quit) => self.dismiss_status(),
+                    _ => None,
+                },
+                // The message can be closed early, like any other overlay
+                A::SoftQuit => self.dismiss_status(),
+                _ => self.handle_events_to_manager(&action),
+            },
+
             CurrentTask::Default(_) => self.handle_events_to_manager(&action),
         }
     }
@@ -69,6 +105,10 @@ impl Component for TaskManager {
             CurrentTask::AddMagnetBar(magnet_bar) => magnet_bar.render(f, rect),
             CurrentTask::DeleteBar(delete_bar) => delete_bar.render(f, rect),
             CurrentTask::FilterBar(filter_bar) => filter_bar.render(f, rect),
+            CurrentTask::MoveBar(move_bar) => move_bar.render(f, rect),
+            CurrentTask::LabelsBar(labels_bar) => labels_bar.render(f, rect),
+            CurrentTask::GoToRowBar(go_to_row_bar) => go_to_row_bar.render(f, rect),
+            CurrentTask::Status(status_bar) => status_bar.render(f, rect),
             CurrentTask::Default(default_bar) => default_bar.render(f, rect),
         }
     }
@@ -82,8 +122,23 @@ impl TaskManager {
                 self.current_task = CurrentTask::AddMagnetBar(AddMagnetBar::new(self.ctx.clone()));
                 Some(Action::SwitchToInputMode)
             }
-            Action::DeleteWithFiles => self.delete_torrent(delete_torrent::Mode::WithFiles),
-            Action::DeleteWithoutFiles => self.delete_torrent(delete_torrent::Mode::WithoutFiles),
+            Action::DeleteWithoutFiles => self.delete_torrent(),
+            Action::Move => self.move_torrent(true),
+            Action::SetLocation => self.move_torrent(false),
+            Action::EditLabels => self.edit_labels(),
+            Action::Reannounce => self.reannounce_torrents(),
+            Action::CopyMagnet => self.copy_magnet(),
+            Action::ShowStatus(text) => {
+                self.current_task = CurrentTask::Status(StatusBar::new(text.clone()));
+                Some(Action::Render)
+            }
+            Action::GoToRow => {
+                self.current_task = CurrentTask::GoToRowBar(GoToRowBar::new(
+                    self.ctx.clone(),
+                    self.table_manager.clone(),
+                ));
+                Some(Action::SwitchToInputMode)
+            }
             Action::Search => {
                 self.current_task = CurrentTask::FilterBar(FilterBar::new(
                     self.ctx.clone(),
@@ -95,17 +150,112 @@ impl TaskManager {
         }
     }
 
-    fn delete_torrent(&mut self, mode: delete_torrent::Mode) -> Option<Action> {
-        if let Some(torrent) = self.table_manager.lock().unwrap().current_torrent() {
-            self.current_task = CurrentTask::DeleteBar(DeleteBar::new(
-                self.ctx.clone(),
-                vec![torrent.id.clone()],
-                mode,
-            ));
-            Some(Action::SwitchToInputMode)
-        } else {
-            None
+    fn delete_torrent(&mut self) -> Option<Action> {
+        let torrent_ids = self.table_manager.lock().unwrap().selected_or_current_ids();
+        if torrent_ids.is_empty() {
+            return None;
+        }
+
+        if !self.ctx.config.confirms(ConfirmAction::DeleteWithoutFiles) {
+            self.ctx
+                .send_torrent_action(TorrentAction::DeleteWithoutFiles(torrent_ids));
+            return None;
+        }
+
+        self.current_task = CurrentTask::DeleteBar(DeleteBar::new(self.ctx.clone(), torrent_ids));
+        Some(Action::SwitchToInputMode)
+    }
+
+    fn move_torrent(&mut self, move_data: bool) -> Option<Action> {
+        let mut table_manager = self.table_manager.lock().unwrap();
+        let torrents = table_manager.selected_or_current_torrents();
+        let current_dir = torrents.first()?.download_dir.clone();
+        let torrent_ids = torrents.iter().map(|torrent| torrent.id.clone()).collect();
+        drop(table_manager);
+
+        self.current_task = CurrentTask::MoveBar(MoveBar::new(
+            self.ctx.clone(),
+            torrent_ids,
+            current_dir,
+            move_data,
+        ));
+        Some(Action::SwitchToInputMode)
+    }
+
+    fn edit_labels(&mut self) -> Option<Action> {
+        let mut table_manager = self.table_manager.lock().unwrap();
+        let torrents = table_manager.selected_or_current_torrents();
+        let current_labels = torrents.first()?.labels.clone();
+        let torrent_ids = torrents.iter().map(|torrent| torrent.id.clone()).collect();
+        drop(table_manager);
+
+        self.current_task = CurrentTask::LabelsBar(LabelsBar::new(
+            self.ctx.clone(),
+            torrent_ids,
+            &current_labels,
+        ));
+        Some(Action::SwitchToInputMode)
+    }
+
+    fn reannounce_torrents(&mut self) -> Option<Action> {
+        let torrent_ids = self.table_manager.lock().unwrap().selected_or_current_ids();
+        if torrent_ids.is_empty() {
+            return None;
         }
+
+        // The action handler reports how it went
+        self.ctx
+            .send_torrent_action(TorrentAction::Reannounce(torrent_ids));
+        None
+    }
+
+    fn copy_magnet(&mut self) -> Option<Action> {
+        let torrent_id = self
+            .table_manager
+            .lock()
+            .unwrap()
+            .current_torrent()?
+            .id
+            .clone();
+        self.ctx
+            .send_torrent_action(TorrentAction::CopyMagnet(torrent_id));
+        None
+    }
+
+    // A bar the user is typing in isn't replaced by the progress
+    pub fn track_progress(&mut self, action: &Action) -> Option<Action> {
+        match (action, &mut self.current_task) {
+            (Action::TaskStarted(text), CurrentTask::Status(_) | CurrentTask::Default(_)) => {
+                self.current_task = CurrentTask::Status(StatusBar::pending(text.clone()));
+                Some(Action::Render)
+            }
+            (Action::TaskFinished(result), CurrentTask::Status(status_bar))
+                if status_bar.is_pending() =>
+            {
+                match result {
+                    Ok(()) => self.dismiss_status(),
+                    Err(error) => {
+                        let style = self.ctx.config.theme.error.to_style();
+                        status_bar.fail(error.clone(), style);
+                        Some(Action::Render)
+                    }
+                }
+            }
+            (Action::TaskDone(text), CurrentTask::Status(status_bar))
+                if status_bar.is_pending() =>
+            {
+                *status_bar = StatusBar::new(text.clone());
+                Some(Action::Render)
+            }
+            _ => unseen_failure(action, &self.current_task)
+                .map(|error_popup| Action::Error(Box::new(error_popup))),
+        }
+    }
+
+    // Status messages don't use input mode, so there's no mode to switch back
+    fn dismiss_status(&mut self) -> Option<Action> {
+        self.current_task = CurrentTask::Default(DefaultBar::new(self.ctx.clone()));
+        Some(Action::Render)
     }
 
     fn finish_task(&mut self) -> Option<Action> {
@@ -117,3 +267,44 @@ impl TaskManager {
         }
     }
 }
+
+// A failure with no pending bar left to show it, because the user is typing
+// in one or another message took its place, goes into a popup instead
+fn unseen_failure(action: &Action, current_task: &CurrentTask) -> Option<ErrorPopup> {
+    match (action, current_task) {
+        (Action::TaskFinished(Err(_)), CurrentTask::Status(status_bar))
+            if status_bar.is_pending() =>
+        {
+            None
+        }
+        (Action::TaskFinished(Err(error)), _) => {
+            Some(ErrorPopup::new("Task failed", error.clone()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed() -> Action {
+        Action::TaskFinished(Err("Failed to reannounce: timed out".to_string()))
+    }
+
+    #[test]
+    fn shows_failures_in_a_pending_bar() {
+        let pending = CurrentTask::Status(StatusBar::pending("Reannouncing…".to_string()));
+
+        assert!(unseen_failure(&failed(), &pending).is_none());
+    }
+
+    #[test]
+    fn reports_failures_once_the_pending_bar_is_gone() {
+        let replaced = CurrentTask::Status(StatusBar::new("Exported 3 torrents".to_string()));
+
+        assert!(unseen_failure(&failed(), &replaced).is_some());
+        assert!(unseen_failure(&Action::TaskFinished(Ok(())), &replaced).is_none());
+        assert!(unseen_failure(&Action::TaskDone("Done".to_string()), &replaced).is_none());
+    }
+}
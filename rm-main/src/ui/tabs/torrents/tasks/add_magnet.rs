@@ -4,14 +4,21 @@ use ratatui::prelude::*;
 use crate::{
     action::Action,
     app,
-    transmission::TorrentAction,
-    ui::{components::Component, tabs::torrents::input_manager::InputManager, to_input_request},
+    transmission::{parse_torrent_list, TorrentAction},
+    ui::{
+        components::{input_field::InputField, Component},
+        to_input_request,
+    },
 };
 
 pub struct AddMagnetBar {
-    input_magnet_mgr: InputManager,
-    input_location_mgr: InputManager,
+    input_magnet_mgr: InputField,
+    input_location_mgr: InputField,
     stage: Stage,
+    // From a multiline paste, added instead of the magnet input
+    batch: Vec<String>,
+    // Toggled with Tab at any stage
+    paused: bool,
     ctx: app::Ctx,
 }
 
@@ -23,21 +30,32 @@ enum Stage {
 impl AddMagnetBar {
     pub fn new(ctx: app::Ctx) -> Self {
         Self {
-            input_magnet_mgr: InputManager::new(
+            input_magnet_mgr: InputField::new(
                 ctx.clone(),
                 "Add (Magnet URL/ Torrent path): ".to_string(),
             ),
-            input_location_mgr: InputManager::new_with_value(
+            input_location_mgr: InputField::new_with_value(
                 ctx.clone(),
                 "Directory: ".to_string(),
-                ctx.session_info.download_dir.clone(),
+                ctx.config
+                    .general
+                    .default_download_dir
+                    .clone()
+                    .unwrap_or_else(|| ctx.session_info().download_dir.clone()),
             ),
             stage: Stage::AskMagnet,
+            batch: vec![],
+            paused: false,
             ctx,
         }
     }
 
     fn handle_input(&mut self, input: KeyEvent) -> Option<Action> {
+        if input.code == KeyCode::Tab {
+            self.paused = !self.paused;
+            return Some(Action::Render);
+        }
+
         match self.stage {
             Stage::AskMagnet => self.handle_magnet_input(input),
             Stage::AskLocation => self.handle_location_input(input),
@@ -62,10 +80,14 @@ impl AddMagnetBar {
 
     fn handle_location_input(&mut self, input: KeyEvent) -> Option<Action> {
         if input.code == KeyCode::Enter {
-            self.ctx.send_torrent_action(TorrentAction::Add(
-                self.input_magnet_mgr.text(),
-                Some(self.input_location_mgr.text()),
-            ));
+            // An empty directory leaves the choice to the daemon
+            let directory = Some(self.input_location_mgr.text()).filter(|dir| !dir.is_empty());
+            let action = if self.batch.is_empty() {
+                TorrentAction::Add(self.input_magnet_mgr.text(), directory, self.paused)
+            } else {
+                TorrentAction::AddMany(std::mem::take(&mut self.batch), directory, self.paused)
+            };
+            self.ctx.send_torrent_action(action);
             return Some(Action::Quit);
         }
         if input.code == KeyCode::Esc {
@@ -78,21 +100,56 @@ impl AddMagnetBar {
         }
         None
     }
+
+    fn handle_paste(&mut self, text: &str) -> Option<Action> {
+        if matches!(self.stage, Stage::AskLocation) {
+            self.input_location_mgr.paste(text);
+            return Some(Action::Render);
+        }
+
+        let mut torrents = parse_torrent_list(text);
+        if torrents.len() > 1 {
+            self.batch = torrents;
+            self.stage = Stage::AskLocation;
+        } else if let Some(torrent) = torrents.pop() {
+            self.input_magnet_mgr.paste(&torrent);
+        }
+        Some(Action::Render)
+    }
 }
 
 impl Component for AddMagnetBar {
-    #[must_use]
     fn handle_actions(&mut self, action: Action) -> Option<Action> {
         match action {
             Action::Input(input) => self.handle_input(input),
+            Action::Paste(text) => self.handle_paste(&text),
             _ => None,
         }
     }
 
     fn render(&mut self, f: &mut Frame, rect: Rect) {
+        let mut spans = vec![];
+        if !self.batch.is_empty() {
+            spans.push(Span::raw(format!(" {} torrents |", self.batch.len())));
+        }
+        spans.extend([
+            Span::raw(" Tab: add paused "),
+            Span::styled(
+                if self.paused { "[x]" } else { "[ ]" },
+                Style::default().fg(self.ctx.config.general.accent_color),
+            ),
+        ]);
+        let paused_hint = Line::from(spans);
+        let [input_rect, hint_rect] = Layout::horizontal([
+            Constraint::Min(0),
+            Constraint::Length(u16::try_from(paused_hint.width()).unwrap_or(u16::MAX)),
+        ])
+        .areas(rect);
+
         match self.stage {
-            Stage::AskMagnet => self.input_magnet_mgr.render(f, rect),
-            Stage::AskLocation => self.input_location_mgr.render(f, rect),
+            Stage::AskMagnet => self.input_magnet_mgr.render(f, input_rect),
+            Stage::AskLocation => self.input_location_mgr.render(f, input_rect),
         }
+        f.render_widget(paused_hint, hint_rect);
     }
 }
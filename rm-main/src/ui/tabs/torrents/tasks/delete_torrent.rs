@@ -6,35 +6,27 @@ use crate::{
     action::Action,
     app,
     transmission::TorrentAction,
-    ui::{components::Component, tabs::torrents::input_manager::InputManager, to_input_request},
+    ui::{
+        components::{input_field::InputField, Component},
+        to_input_request,
+    },
 };
 
 pub struct DeleteBar {
     torrents_to_delete: Vec<Id>,
     ctx: app::Ctx,
-    input_mgr: InputManager,
-    mode: Mode,
-}
-
-pub enum Mode {
-    WithFiles,
-    WithoutFiles,
+    input_mgr: InputField,
 }
 
+// Deleting with files goes through `ConfirmationPopup` instead
 impl DeleteBar {
-    pub fn new(ctx: app::Ctx, to_delete: Vec<Id>, mode: Mode) -> Self {
-        let prompt = {
-            match mode {
-                Mode::WithFiles => "Really delete selected WITH files? (y/n) ".to_string(),
-                Mode::WithoutFiles => "Really delete selected without files? (y/n) ".to_string(),
-            }
-        };
+    pub fn new(ctx: app::Ctx, to_delete: Vec<Id>) -> Self {
+        let prompt = "Really delete selected without files? (y/n) ".to_string();
 
         Self {
             torrents_to_delete: to_delete,
-            input_mgr: InputManager::new(ctx.clone(), prompt),
+            input_mgr: InputField::new(ctx.clone(), prompt),
             ctx,
-            mode,
         }
     }
 }
@@ -51,17 +43,10 @@ impl Component for DeleteBar {
                     let text = self.input_mgr.text().to_lowercase();
                     if text == "y" || text == "yes" {
                         let torrents_to_delete = self.torrents_to_delete.clone();
-                        match self.mode {
-                            Mode::WithFiles => self.ctx.send_torrent_action(
-                                TorrentAction::DeleteWithFiles(torrents_to_delete),
-                            ),
-                            Mode::WithoutFiles => {
-                                self.ctx
-                                    .send_torrent_action(TorrentAction::DeleteWithoutFiles(
-                                        torrents_to_delete,
-                                    ))
-                            }
-                        }
+                        self.ctx
+                            .send_torrent_action(TorrentAction::DeleteWithoutFiles(
+                                torrents_to_delete,
+                            ));
                         return Some(Action::Quit);
                     } else if text == "n" || text == "no" {
                         return Some(Action::Quit);
@@ -75,6 +60,10 @@ impl Component for DeleteBar {
 
                 None
             }
+            Action::Paste(text) => {
+                self.input_mgr.paste(&text);
+                Some(Action::Render)
+            }
             _ => None,
         }
     }
@@ -1,70 +1,104 @@
 use std::sync::{Arc, Mutex};
 
 use crossterm::event::KeyCode;
-use ratatui::prelude::*;
+use ratatui::{prelude::*, widgets::Paragraph};
+use rm_config::FilterMode;
 
 use crate::{
     action::Action,
     app,
     ui::{
-        components::Component,
-        tabs::torrents::{input_manager::InputManager, TableManager},
+        components::{input_field::InputField, Component},
+        matcher::Matcher,
+        tabs::torrents::TableManager,
         to_input_request,
     },
 };
 
 pub struct FilterBar {
-    input: InputManager,
+    input: InputField,
     table_manager: Arc<Mutex<TableManager>>,
+    // Restored on Esc
+    original_filter: Option<String>,
+    filter_mode: FilterMode,
+    is_invalid: bool,
 }
 
 impl FilterBar {
     pub fn new(ctx: app::Ctx, table_manager: Arc<Mutex<TableManager>>) -> Self {
         let current_filter = table_manager.lock().unwrap().filter.lock().unwrap().clone();
-        let input = InputManager::new_with_value(
+        let filter_mode = ctx.config.torrents_tab.filter_mode;
+        let input = InputField::new_with_value(
             ctx,
             "Search: ".to_string(),
-            current_filter.unwrap_or_default(),
+            current_filter.clone().unwrap_or_default(),
         );
         Self {
             input,
             table_manager,
+            is_invalid: current_filter
+                .as_deref()
+                .is_some_and(|filter| Matcher::new(filter_mode, filter).is_err()),
+            original_filter: current_filter,
+            filter_mode,
         }
     }
 }
 
+impl FilterBar {
+    // The table follows the filter while it's typed
+    fn apply_filter(&mut self) -> Option<Action> {
+        self.is_invalid = Matcher::new(self.filter_mode, &self.input.text()).is_err();
+        let table_manager_lock = self.table_manager.lock().unwrap();
+        table_manager_lock
+            .filter
+            .lock()
+            .unwrap()
+            .replace(self.input.text());
+        table_manager_lock.table.state.borrow_mut().select(Some(0));
+        Some(Action::Render)
+    }
+}
+
 impl Component for FilterBar {
     fn handle_actions(&mut self, action: Action) -> Option<Action> {
         match action {
             Action::Input(input) => {
-                if matches!(input.code, KeyCode::Enter | KeyCode::Esc) {
+                if input.code == KeyCode::Enter {
                     if self.input.text().is_empty() {
                         *self.table_manager.lock().unwrap().filter.lock().unwrap() = None;
                     }
                     return Some(Action::Quit);
                 }
 
+                if input.code == KeyCode::Esc {
+                    let table_manager_lock = self.table_manager.lock().unwrap();
+                    *table_manager_lock.filter.lock().unwrap() = self.original_filter.take();
+                    table_manager_lock.table.state.borrow_mut().select(Some(0));
+                    return Some(Action::Quit);
+                }
+
                 if let Some(req) = to_input_request(input) {
                     self.input.handle(req);
-                    {
-                        let table_manager_lock = self.table_manager.lock().unwrap();
-                        table_manager_lock
-                            .filter
-                            .lock()
-                            .unwrap()
-                            .replace(self.input.text());
-                        table_manager_lock.table.state.borrow_mut().select(Some(0));
-                    }
-                    return Some(Action::Render);
+                    return self.apply_filter();
                 }
 
                 None
             }
+            Action::Paste(text) => {
+                self.input.paste(&text);
+                self.apply_filter()
+            }
             _ => None,
         }
     }
 
     fn render(&mut self, f: &mut Frame, rect: Rect) {
         self.input.render(f, rect);
+
+        if self.is_invalid {
+            let error = Paragraph::new("invalid regex ".red()).alignment(Alignment::Right);
+            f.render_widget(error, rect);
+        }
     }
 }
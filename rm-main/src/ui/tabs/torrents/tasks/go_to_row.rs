@@ -0,0 +1,80 @@
+use std::sync::{Arc, Mutex};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use tui_input::InputRequest;
+
+use crate::{
+    action::Action,
+    app,
+    ui::{
+        components::{input_field::InputField, Component},
+        tabs::torrents::TableManager,
+        to_input_request,
+    },
+};
+
+pub struct GoToRowBar {
+    input_mgr: InputField,
+    table_manager: Arc<Mutex<TableManager>>,
+}
+
+impl GoToRowBar {
+    pub fn new(ctx: app::Ctx, table_manager: Arc<Mutex<TableManager>>) -> Self {
+        Self {
+            input_mgr: InputField::new(ctx, "Go to row: ".to_string()),
+            table_manager,
+        }
+    }
+
+    // Rows are numbered from 1, and numbers past the end go to the last row
+    fn go_to_row(&self) {
+        let Ok(row) = self.input_mgr.text().parse::<usize>() else {
+            return;
+        };
+
+        let table_manager = self.table_manager.lock().unwrap();
+        let len = table_manager.table.get_len();
+        if len == 0 {
+            return;
+        }
+        let index = row.saturating_sub(1).min(len - 1);
+        table_manager.table.state.borrow_mut().select(Some(index));
+    }
+
+    fn handle_input(&mut self, input: KeyEvent) -> Option<Action> {
+        if input.code == KeyCode::Enter {
+            self.go_to_row();
+            return Some(Action::Quit);
+        }
+        if input.code == KeyCode::Esc {
+            return Some(Action::Quit);
+        }
+
+        match to_input_request(input) {
+            Some(InputRequest::InsertChar(char)) if !char.is_ascii_digit() => None,
+            Some(req) => {
+                self.input_mgr.handle(req);
+                Some(Action::Render)
+            }
+            None => None,
+        }
+    }
+}
+
+impl Component for GoToRowBar {
+    fn handle_actions(&mut self, action: Action) -> Option<Action> {
+        match action {
+            Action::Input(input) => self.handle_input(input),
+            Action::Paste(text) if text.trim().chars().all(|char| char.is_ascii_digit()) => {
+                self.input_mgr.paste(text.trim());
+                Some(Action::Render)
+            }
+            _ => None,
+        }
+    }
+
+    fn render(&mut self, f: &mut Frame, rect: Rect) {
+        self.input_mgr.render(f, rect)
+    }
+}
@@ -0,0 +1,82 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use transmission_rpc::types::{Id, TorrentSetArgs};
+
+use crate::{
+    action::Action,
+    app,
+    transmission::TorrentAction,
+    ui::{
+        components::{input_field::InputField, Component},
+        to_input_request,
+    },
+};
+
+pub struct LabelsBar {
+    torrents_to_label: Vec<Id>,
+    ctx: app::Ctx,
+    input_mgr: InputField,
+}
+
+impl LabelsBar {
+    pub fn new(ctx: app::Ctx, to_label: Vec<Id>, current_labels: &[String]) -> Self {
+        Self {
+            torrents_to_label: to_label,
+            input_mgr: InputField::new_with_value(
+                ctx.clone(),
+                "Labels: ".to_string(),
+                current_labels.join(", "),
+            ),
+            ctx,
+        }
+    }
+
+    // Labels are separated with commas, and an empty input removes them all.
+    fn parse_labels(text: &str) -> Vec<String> {
+        let mut labels: Vec<String> = vec![];
+        for label in text.split(',').map(str::trim) {
+            if !label.is_empty() && !labels.iter().any(|existing| existing == label) {
+                labels.push(label.to_string());
+            }
+        }
+        labels
+    }
+
+    fn handle_input(&mut self, input: KeyEvent) -> Option<Action> {
+        if input.code == KeyCode::Enter {
+            let labels = Self::parse_labels(&self.input_mgr.text());
+            let args = TorrentSetArgs::default().labels(labels);
+            self.ctx.send_torrent_action(TorrentAction::SetArgs(
+                Box::new(args),
+                Some(self.torrents_to_label.clone()),
+            ));
+            return Some(Action::Quit);
+        }
+        if input.code == KeyCode::Esc {
+            return Some(Action::Quit);
+        }
+
+        if let Some(req) = to_input_request(input) {
+            self.input_mgr.handle(req);
+            return Some(Action::Render);
+        }
+        None
+    }
+}
+
+impl Component for LabelsBar {
+    fn handle_actions(&mut self, action: Action) -> Option<Action> {
+        match action {
+            Action::Input(input) => self.handle_input(input),
+            Action::Paste(text) => {
+                self.input_mgr.paste(&text);
+                Some(Action::Render)
+            }
+            _ => None,
+        }
+    }
+
+    fn render(&mut self, f: &mut Frame, rect: Rect) {
+        self.input_mgr.render(f, rect)
+    }
+}
@@ -2,3 +2,7 @@ pub mod add_magnet;
 pub mod default;
 pub mod delete_torrent;
 pub mod filter;
+pub mod go_to_row;
+pub mod labels;
+pub mod move_torrent;
+pub mod status;
@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use transmission_rpc::types::Id;
+
+use crate::{
+    action::Action,
+    app,
+    transmission::TorrentAction,
+    ui::{
+        components::{input_field::InputField, Component},
+        to_input_request,
+    },
+};
+
+pub struct MoveBar {
+    torrents_to_move: Vec<Id>,
+    ctx: app::Ctx,
+    input_mgr: InputField,
+    // Otherwise only the daemon is told where the data is now
+    move_data: bool,
+}
+
+impl MoveBar {
+    pub fn new(ctx: app::Ctx, to_move: Vec<Id>, current_dir: String, move_data: bool) -> Self {
+        let prompt = if move_data {
+            "Move to: "
+        } else {
+            "Set location to: "
+        };
+        Self {
+            torrents_to_move: to_move,
+            input_mgr: InputField::new_with_value(ctx.clone(), prompt.to_string(), current_dir),
+            ctx,
+            move_data,
+        }
+    }
+
+    fn handle_input(&mut self, input: KeyEvent) -> Option<Action> {
+        if input.code == KeyCode::Enter {
+            let location = self.input_mgr.text();
+            if location.is_empty() {
+                return None;
+            }
+
+            let torrents_to_move = self.torrents_to_move.clone();
+            self.ctx.send_torrent_action(TorrentAction::Move(
+                torrents_to_move,
+                PathBuf::from(location),
+                self.move_data,
+            ));
+            return Some(Action::Quit);
+        }
+        if input.code == KeyCode::Esc {
+            return Some(Action::Quit);
+        }
+
+        if let Some(req) = to_input_request(input) {
+            self.input_mgr.handle(req);
+            return Some(Action::Render);
+        }
+        None
+    }
+}
+
+impl Component for MoveBar {
+    fn handle_actions(&mut self, action: Action) -> Option<Action> {
+        match action {
+            Action::Input(input) => self.handle_input(input),
+            Action::Paste(text) => {
+                self.input_mgr.paste(&text);
+                Some(Action::Render)
+            }
+            _ => None,
+        }
+    }
+
+    fn render(&mut self, f: &mut Frame, rect: Rect) {
+        self.input_mgr.render(f, rect)
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::{Duration, Instant};
+
+use ratatui::prelude::*;
+
+use crate::{action::Action, ui::components::Component};
+
+const STATUS_DURATION: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Shown,
+    // Waiting for the daemon, stays until the action is done
+    Pending,
+    Failed(Style),
+}
+
+// A short message confirming that an action was received
+pub struct StatusBar {
+    text: String,
+    shown_at: Instant,
+    state: State,
+}
+
+impl StatusBar {
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            shown_at: Instant::now(),
+            state: State::Shown,
+        }
+    }
+
+    pub fn pending(text: String) -> Self {
+        Self {
+            state: State::Pending,
+            ..Self::new(text)
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.state == State::Pending
+    }
+
+    // Shown for as long as any other message
+    pub fn fail(&mut self, error: String, style: Style) {
+        self.text = error;
+        self.shown_at = Instant::now();
+        self.state = State::Failed(style);
+    }
+}
+
+impl Component for StatusBar {
+    fn tick(&mut self) -> Option<Action> {
+        if !self.is_pending() && self.shown_at.elapsed() >= STATUS_DURATION {
+            Some(Action::Quit)
+        } else {
+            None
+        }
+    }
+
+    fn render(&mut self, f: &mut Frame, rect: Rect) {
+        let style = match self.state {
+            State::Failed(style) => style,
+            State::Shown | State::Pending => Style::default(),
+        };
+        f.render_widget(Span::styled(self.text.as_str(), style), rect);
+    }
+}
@@ -1,27 +1,6 @@
-pub fn bytes_to_human_format(bytes: i64) -> String {
-    const KB: f64 = 1024.0;
-    const MB: f64 = KB * 1024.0;
-    const GB: f64 = MB * 1024.0;
-    const TB: f64 = GB * 1024.0;
-
-    if bytes == 0 {
-        return "0 B".to_string();
-    }
-
-    let (value, unit) = if bytes < (KB - 25f64) as i64 {
-        (bytes as f64, "B")
-    } else if bytes < (MB - 25f64) as i64 {
-        (bytes as f64 / KB, "KB")
-    } else if bytes < (GB - 25f64) as i64 {
-        (bytes as f64 / MB, "MB")
-    } else if bytes < (TB - 25f64) as i64 {
-        (bytes as f64 / GB, "GB")
-    } else {
-        (bytes as f64 / TB, "TB")
-    };
-
-    format!("{value:.1} {unit}")
-}
+use ratatui::text::{Line, Span};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 pub fn seconds_to_human_format(seconds: i64) -> String {
     const MINUTE: i64 = 60;
@@ -63,15 +42,116 @@ pub fn seconds_to_human_format(seconds: i64) -> String {
 }
 
 pub fn download_speed_format(download_speed: &str) -> String {
-    if download_speed.len() > 0 {
+    if !download_speed.is_empty() {
         return format!("▼ {}", download_speed);
     }
     download_speed.to_string()
 }
 
 pub fn upload_speed_format(upload_speed: &str) -> String {
-    if upload_speed.len() > 0 {
+    if !upload_speed.is_empty() {
         return format!("▲ {}", upload_speed);
     }
     upload_speed.to_string()
 }
+
+// Cuts the line to the width, ending it with "…" if anything was left out.
+// Whole graphemes are kept, so that wide or combined characters aren't split.
+pub fn truncate_line(mut line: Line<'_>, width: usize) -> Line<'_> {
+    if line.width() <= width {
+        return line;
+    }
+
+    let mut room = width.saturating_sub(1);
+    let mut spans = Vec::with_capacity(line.spans.len());
+    let mut ellipsis_style = line.style;
+    for span in std::mem::take(&mut line.spans) {
+        let mut end = 0;
+        for grapheme in span.content.graphemes(true) {
+            let grapheme_width = grapheme.width();
+            if grapheme_width > room {
+                room = 0;
+                break;
+            }
+            room -= grapheme_width;
+            end += grapheme.len();
+        }
+
+        ellipsis_style = span.style;
+        if end == span.content.len() {
+            spans.push(span);
+            continue;
+        }
+        if end > 0 {
+            spans.push(Span::styled(span.content[..end].to_string(), span.style));
+        }
+        break;
+    }
+
+    if width > 0 {
+        spans.push(Span::styled("…", ellipsis_style));
+    }
+    line.spans = spans;
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn truncated(text: &'static str, width: usize) -> String {
+        truncate_line(Line::from(text), width).to_string()
+    }
+
+    #[test]
+    fn keeps_lines_that_fit() {
+        assert_eq!(truncated("ubuntu.iso", 10), "ubuntu.iso");
+        assert_eq!(truncated("日本語", 6), "日本語");
+    }
+
+    #[test]
+    fn cuts_before_a_wide_character_at_the_border() {
+        // "本" would end one column past the border
+        assert_eq!(truncated("日本語", 4), "日…");
+        assert_eq!(truncated("日本語", 5), "日本…");
+    }
+
+    #[test]
+    fn keeps_combining_marks_with_their_letter() {
+        assert_eq!(truncated("e\u{301}e\u{301}e\u{301}", 2), "e\u{301}…");
+    }
+
+    #[test]
+    fn keeps_emoji_sequences_whole() {
+        let family = "👨\u{200d}👩\u{200d}👧";
+        let line = format!("ab{family}cd");
+
+        assert_eq!(
+            truncate_line(Line::from(line.as_str()), 3).to_string(),
+            "ab…"
+        );
+        for width in 0..=line.width() {
+            let truncated = truncate_line(Line::from(line.as_str()), width).to_string();
+            let kept = truncated.trim_end_matches('…');
+            assert!(line.starts_with(kept));
+            assert!(
+                !kept.contains('\u{200d}') || kept.contains(family),
+                "{truncated}"
+            );
+        }
+    }
+
+    #[test]
+    fn never_gets_wider_than_the_width() {
+        let line = Line::from(vec![
+            Span::raw("日本"),
+            Span::raw("e\u{301}x"),
+            Span::raw("👨\u{200d}👩\u{200d}👧"),
+            Span::raw("語"),
+        ]);
+        for width in 0..=line.width() {
+            let truncated = truncate_line(line.clone(), width);
+            assert!(truncated.width() <= width, "{truncated} at {width}");
+        }
+    }
+}